@@ -0,0 +1,4 @@
+// 迁移文件通过 sqlx::migrate! 嵌入二进制文件，迁移目录变化时需要重新编译
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
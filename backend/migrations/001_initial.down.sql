@@ -0,0 +1,9 @@
+-- Revert 001_initial
+DROP TABLE IF EXISTS friendships;
+DROP TABLE IF EXISTS comments;
+DROP TABLE IF EXISTS content_likes;
+DROP TABLE IF EXISTS contents;
+DROP TABLE IF EXISTS users;
+
+DROP FUNCTION IF EXISTS update_content_likes_count();
+DROP FUNCTION IF EXISTS update_updated_at_column();
@@ -0,0 +1,2 @@
+-- Revert 002_refresh_tokens
+DROP TABLE IF EXISTS refresh_tokens;
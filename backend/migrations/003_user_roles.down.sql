@@ -0,0 +1,4 @@
+-- Revert 003_user_roles
+ALTER TABLE users
+    DROP CONSTRAINT IF EXISTS users_role_check,
+    DROP COLUMN IF EXISTS role;
@@ -0,0 +1,4 @@
+-- Add role column to users for role-based authorization
+ALTER TABLE users
+    ADD COLUMN role VARCHAR(20) NOT NULL DEFAULT 'learner',
+    ADD CONSTRAINT users_role_check CHECK (role IN ('learner', 'teacher', 'moderator', 'admin'));
@@ -0,0 +1,6 @@
+-- Revert 004_list_indexes
+DROP INDEX IF EXISTS idx_contents_tags;
+DROP INDEX IF EXISTS idx_contents_content_type;
+DROP INDEX IF EXISTS idx_contents_likes_created_at_id;
+DROP INDEX IF EXISTS idx_contents_created_at_id;
+DROP INDEX IF EXISTS idx_users_created_at_id;
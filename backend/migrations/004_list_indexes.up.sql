@@ -0,0 +1,6 @@
+-- Indexes backing keyset pagination and list filters
+CREATE INDEX idx_users_created_at_id ON users(created_at DESC, id DESC);
+CREATE INDEX idx_contents_created_at_id ON contents(created_at DESC, id DESC);
+CREATE INDEX idx_contents_likes_created_at_id ON contents(likes_count DESC, created_at DESC, id DESC);
+CREATE INDEX idx_contents_content_type ON contents(content_type);
+CREATE INDEX idx_contents_tags ON contents USING GIN(tags);
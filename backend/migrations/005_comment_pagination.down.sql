@@ -0,0 +1,2 @@
+-- Revert 005_comment_pagination
+DROP INDEX IF EXISTS idx_comments_content_created_at_id;
@@ -0,0 +1,2 @@
+-- Index backing keyset pagination of comments under a content item
+CREATE INDEX idx_comments_content_created_at_id ON comments(content_id, created_at, id);
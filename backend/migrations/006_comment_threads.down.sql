@@ -0,0 +1,10 @@
+-- Revert 006_comment_threads; existing replies become top-level comments
+DROP TRIGGER IF EXISTS update_comment_reply_count_trigger ON comments;
+DROP FUNCTION IF EXISTS update_comment_reply_count();
+
+DROP INDEX IF EXISTS idx_comments_parent_created_at_id;
+
+ALTER TABLE comments
+    DROP COLUMN IF EXISTS reply_count,
+    DROP COLUMN IF EXISTS depth,
+    DROP COLUMN IF EXISTS parent_id;
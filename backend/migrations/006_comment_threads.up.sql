@@ -0,0 +1,26 @@
+-- Threaded comments: replies reference their parent comment
+ALTER TABLE comments
+    ADD COLUMN parent_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    ADD COLUMN depth INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN reply_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_comments_parent_created_at_id ON comments(parent_id, created_at, id);
+
+-- Keep reply_count in sync with direct replies
+CREATE OR REPLACE FUNCTION update_comment_reply_count()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' AND NEW.parent_id IS NOT NULL THEN
+        UPDATE comments SET reply_count = reply_count + 1 WHERE id = NEW.parent_id;
+        RETURN NEW;
+    ELSIF TG_OP = 'DELETE' AND OLD.parent_id IS NOT NULL THEN
+        UPDATE comments SET reply_count = reply_count - 1 WHERE id = OLD.parent_id;
+        RETURN OLD;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER update_comment_reply_count_trigger
+    AFTER INSERT OR DELETE ON comments
+    FOR EACH ROW EXECUTE FUNCTION update_comment_reply_count();
@@ -0,0 +1,13 @@
+-- Revert 007_friendship_lifecycle; declined requests have no equivalent in the old status set
+DROP INDEX IF EXISTS idx_friendships_pair;
+
+DELETE FROM friendships WHERE status = 'declined';
+ALTER TABLE friendships DROP CONSTRAINT friendships_status_check;
+ALTER TABLE friendships
+    ADD CONSTRAINT friendships_status_check CHECK (status IN ('pending', 'accepted', 'blocked'));
+
+ALTER TABLE friendships
+    DROP COLUMN IF EXISTS blocked_by,
+    DROP COLUMN IF EXISTS responded_at,
+    DROP COLUMN IF EXISTS request_message,
+    DROP COLUMN IF EXISTS requested_by;
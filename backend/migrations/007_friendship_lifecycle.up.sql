@@ -0,0 +1,17 @@
+-- Friendship request lifecycle: track who initiated, when it was answered and who blocked
+ALTER TABLE friendships
+    ADD COLUMN requested_by UUID REFERENCES users(id) ON DELETE CASCADE,
+    ADD COLUMN request_message VARCHAR(500),
+    ADD COLUMN responded_at TIMESTAMPTZ,
+    ADD COLUMN blocked_by UUID REFERENCES users(id) ON DELETE CASCADE;
+
+UPDATE friendships SET requested_by = user1_id WHERE requested_by IS NULL;
+ALTER TABLE friendships ALTER COLUMN requested_by SET NOT NULL;
+
+ALTER TABLE friendships DROP CONSTRAINT friendships_status_check;
+ALTER TABLE friendships
+    ADD CONSTRAINT friendships_status_check CHECK (status IN ('pending', 'accepted', 'declined', 'blocked'));
+
+-- A pair of users may only have one relationship row, regardless of direction (A→B vs B→A)
+CREATE UNIQUE INDEX idx_friendships_pair
+    ON friendships (LEAST(user1_id, user2_id), GREATEST(user1_id, user2_id));
@@ -0,0 +1,3 @@
+-- Revert 008_ai_conversations
+DROP TABLE IF EXISTS ai_messages;
+DROP TABLE IF EXISTS ai_conversations;
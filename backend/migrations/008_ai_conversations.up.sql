@@ -0,0 +1,20 @@
+-- AI tutoring conversations and their message history
+CREATE TABLE ai_conversations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(200),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE ai_messages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    conversation_id UUID NOT NULL REFERENCES ai_conversations(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (role IN ('system', 'user', 'assistant'))
+);
+
+CREATE INDEX idx_ai_conversations_user_created_at_id ON ai_conversations(user_id, created_at DESC, id DESC);
+CREATE INDEX idx_ai_messages_conversation_created_at ON ai_messages(conversation_id, created_at);
@@ -0,0 +1,2 @@
+-- Revert 009_content_chunks
+DROP TABLE IF EXISTS content_chunks;
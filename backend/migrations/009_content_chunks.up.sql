@@ -0,0 +1,14 @@
+-- Embedded passages of content bodies used for retrieval-augmented AI answers.
+-- Vectors are kept as REAL[] so no extension is required; search runs in-process.
+CREATE TABLE content_chunks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    content_id UUID NOT NULL REFERENCES contents(id) ON DELETE CASCADE,
+    chunk_index INTEGER NOT NULL,
+    body TEXT NOT NULL,
+    embedding REAL[] NOT NULL,
+    embedding_model VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(content_id, chunk_index)
+);
+
+CREATE INDEX idx_content_chunks_embedding_model ON content_chunks(embedding_model);
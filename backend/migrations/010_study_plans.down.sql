@@ -0,0 +1,2 @@
+-- Revert 010_study_plans
+DROP TABLE IF EXISTS study_plans;
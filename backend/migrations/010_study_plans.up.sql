@@ -0,0 +1,13 @@
+-- Saved study plans; the plan body is a validated JSON document
+CREATE TABLE study_plans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    subject VARCHAR(200) NOT NULL,
+    level VARCHAR(50) NOT NULL,
+    goals TEXT NOT NULL DEFAULT '',
+    plan JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_study_plans_user_created_at_id ON study_plans(user_id, created_at DESC, id DESC);
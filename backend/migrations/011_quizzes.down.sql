@@ -0,0 +1,3 @@
+-- Revert 011_quizzes
+DROP TABLE IF EXISTS quiz_submissions;
+DROP TABLE IF EXISTS quizzes;
@@ -0,0 +1,2 @@
+-- Revert 012_ai_usage
+DROP TABLE IF EXISTS ai_usage;
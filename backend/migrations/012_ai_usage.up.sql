@@ -0,0 +1,15 @@
+-- Per-request AI usage ledger used for quota enforcement and admin reporting.
+-- Rows are inserted when a metered request starts and token counts are filled in when it finishes.
+CREATE TABLE ai_usage (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ip VARCHAR(64) NOT NULL,
+    endpoint VARCHAR(100) NOT NULL,
+    prompt_tokens BIGINT NOT NULL DEFAULT 0,
+    completion_tokens BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_ai_usage_user_created_at ON ai_usage(user_id, created_at) WHERE user_id IS NOT NULL;
+CREATE INDEX idx_ai_usage_ip_created_at ON ai_usage(ip, created_at) WHERE user_id IS NULL;
+CREATE INDEX idx_ai_usage_created_at ON ai_usage(created_at);
@@ -0,0 +1,7 @@
+-- Revert 013_prompt_templates
+ALTER TABLE quizzes DROP COLUMN IF EXISTS prompt_version;
+ALTER TABLE ai_usage
+    DROP COLUMN IF EXISTS prompt_version,
+    DROP COLUMN IF EXISTS prompt_name;
+
+DROP TABLE IF EXISTS prompt_templates;
@@ -0,0 +1,25 @@
+-- Versioned prompt templates. Versions are immutable; weight controls how traffic is split
+-- between the active versions of a prompt (0 = inactive). Prompts without an active version
+-- fall back to the built-in template shipped with the server (version 0).
+CREATE TABLE prompt_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    version INTEGER NOT NULL,
+    template TEXT NOT NULL,
+    description TEXT,
+    weight INTEGER NOT NULL DEFAULT 0 CHECK (weight BETWEEN 0 AND 100),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (name, version)
+);
+
+CREATE TRIGGER update_prompt_templates_updated_at BEFORE UPDATE ON prompt_templates
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Prompt version used by each metered AI request, for comparing versions
+ALTER TABLE ai_usage ADD COLUMN prompt_name VARCHAR(100);
+ALTER TABLE ai_usage ADD COLUMN prompt_version INTEGER;
+
+-- Prompt version a quiz was generated with, so submission scores can be compared per version
+ALTER TABLE quizzes ADD COLUMN prompt_version INTEGER;
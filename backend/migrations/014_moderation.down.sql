@@ -0,0 +1,3 @@
+-- Revert 014_moderation
+DROP TABLE IF EXISTS moderation_incidents;
+DROP TABLE IF EXISTS moderation_rules;
@@ -0,0 +1,42 @@
+-- Keyword/regex moderation rules applied to AI input and output
+CREATE TABLE moderation_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('keyword', 'regex')),
+    pattern TEXT NOT NULL,
+    action VARCHAR(20) NOT NULL CHECK (action IN ('block', 'redact')),
+    category VARCHAR(50) NOT NULL,
+    description TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_moderation_rules_updated_at BEFORE UPDATE ON moderation_rules
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Every blocked, redacted or classifier-flagged message
+CREATE TABLE moderation_incidents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    feature VARCHAR(50) NOT NULL,
+    stage VARCHAR(20) NOT NULL CHECK (stage IN ('input', 'output')),
+    action VARCHAR(20) NOT NULL CHECK (action IN ('block', 'redact')),
+    source VARCHAR(20) NOT NULL CHECK (source IN ('rule', 'classifier')),
+    rule_id UUID REFERENCES moderation_rules(id) ON DELETE SET NULL,
+    category VARCHAR(50) NOT NULL,
+    excerpt TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_moderation_incidents_created_at_id ON moderation_incidents(created_at DESC, id DESC);
+
+-- Starter rules: redact personal contact details and block a few clearly harmful requests.
+-- Administrators can disable or extend them through the admin API.
+INSERT INTO moderation_rules (kind, pattern, action, category, description) VALUES
+    ('regex', '\d{17}[\dXx]', 'redact', 'privacy', '身份证号'),
+    ('regex', '1[3-9]\d{9}', 'redact', 'privacy', '手机号'),
+    ('regex', '[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}', 'redact', 'privacy', '邮箱地址'),
+    ('keyword', '制作炸弹', 'block', 'violence', NULL),
+    ('keyword', '自杀方法', 'block', 'self_harm', NULL),
+    ('keyword', '购买毒品', 'block', 'drugs', NULL),
+    ('keyword', '网络赌博', 'block', 'gambling', NULL);
@@ -0,0 +1,8 @@
+-- Revert 015_moderation_digit_boundaries
+UPDATE moderation_rules
+SET pattern = '\d{17}[\dXx]'
+WHERE kind = 'regex' AND pattern = '(?P<before>^|\D)\d{17}[\dXx](?P<after>\D|$)';
+
+UPDATE moderation_rules
+SET pattern = '1[3-9]\d{9}'
+WHERE kind = 'regex' AND pattern = '(?P<before>^|\D)1[3-9]\d{9}(?P<after>\D|$)';
@@ -0,0 +1,10 @@
+-- The starter ID-number and phone rules matched inside longer digit runs (order numbers,
+-- timestamps). The regex crate has no lookaround, so the boundaries are written as the
+-- named groups `before` / `after`, which redaction keeps in place.
+UPDATE moderation_rules
+SET pattern = '(?P<before>^|\D)\d{17}[\dXx](?P<after>\D|$)'
+WHERE kind = 'regex' AND pattern = '\d{17}[\dXx]';
+
+UPDATE moderation_rules
+SET pattern = '(?P<before>^|\D)1[3-9]\d{9}(?P<after>\D|$)'
+WHERE kind = 'regex' AND pattern = '1[3-9]\d{9}';
@@ -0,0 +1,31 @@
+use super::{ChatMessage, ChatRole};
+
+// 每次请求附带的历史对话上限（估算的 token 数）
+pub const HISTORY_TOKEN_BUDGET: usize = 3000;
+
+// 粗略估算：ASCII 约 4 个字符一个 token，其他字符（中文等）约一个字一个 token
+pub fn estimate_tokens(text: &str) -> usize {
+    let (ascii, other): (usize, usize) = text
+        .chars()
+        .fold((0, 0), |(ascii, other), c| if c.is_ascii() { (ascii + 1, other) } else { (ascii, other + 1) });
+    ascii.div_ceil(4) + other
+}
+
+// 从最近的消息往前保留，直到超出预算；保留部分总是从用户提问开始
+pub fn truncate_history(history: Vec<ChatMessage>, budget: usize) -> Vec<ChatMessage> {
+    let mut used = 0;
+    let mut keep = history.len();
+    for (index, message) in history.iter().enumerate().rev() {
+        used += estimate_tokens(&message.content);
+        if used > budget {
+            break;
+        }
+        keep = index;
+    }
+
+    let mut kept: Vec<ChatMessage> = history.into_iter().skip(keep).collect();
+    while kept.first().is_some_and(|message| message.role != ChatRole::User) {
+        kept.remove(0);
+    }
+    kept
+}
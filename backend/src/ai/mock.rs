@@ -0,0 +1,218 @@
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{AiProvider, ChatMessage, ChatRole, DeltaStream};
+
+pub const MOCK_EMBEDDING_DIM: usize = 64;
+
+// 确定性的本地实现：相同输入总是得到相同输出，便于测试和离线开发
+pub struct MockProvider;
+
+impl MockProvider {
+    fn reply(messages: &[ChatMessage]) -> String {
+        let question = messages
+            .iter()
+            .rev()
+            .find(|message| message.role == ChatRole::User)
+            .map(|message| message.content.as_str())
+            .unwrap_or_default();
+
+        format!("这是对问题「{}」的AI回答。当前使用的是模拟AI服务。", question)
+    }
+
+    // 哈希技巧：每个词（中文按字）散列到固定维度上，再做 L2 归一化
+    fn embedding(input: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; MOCK_EMBEDDING_DIM];
+        let lowered = input.to_lowercase();
+        let tokens = lowered.split(|c: char| !c.is_alphanumeric()).flat_map(|word| {
+            if word.is_ascii() {
+                vec![word.to_string()]
+            } else {
+                word.chars().map(String::from).collect()
+            }
+        });
+
+        for token in tokens.filter(|token| !token.is_empty()) {
+            let digest = Sha256::digest(token.as_bytes());
+            let index = usize::from(digest[0]) % MOCK_EMBEDDING_DIM;
+            let sign = if digest[1] & 1 == 0 { 1.0 } else { -1.0 };
+            vector[index] += sign;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        vector
+    }
+
+    // 按 schema 生成一个满足约束的最小示例：数组取一个元素，数值取下限，字符串为占位文本
+    fn example(schema: &Value, root: &Value) -> Value {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let target = reference
+                .strip_prefix("#/")
+                .map(|path| path.split('/').fold(root, |node, key| &node[key]))
+                .unwrap_or(&Value::Null);
+            return Self::example(target, root);
+        }
+        if let Some(options) = schema.get("anyOf").or_else(|| schema.get("oneOf")).and_then(Value::as_array) {
+            return options.first().map(|option| Self::example(option, root)).unwrap_or(Value::Null);
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            return values.first().cloned().unwrap_or(Value::Null);
+        }
+
+        let schema_type = match schema.get("type") {
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).find(|t| *t != "null"),
+            Some(Value::String(schema_type)) => Some(schema_type.as_str()),
+            _ => None,
+        };
+        let minimum = schema.get("minimum").and_then(Value::as_f64).unwrap_or(1.0).max(1.0);
+        let minimum = schema.get("maximum").and_then(Value::as_f64).map_or(minimum, |max| minimum.min(max));
+
+        match schema_type {
+            Some("object") => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                let object: Map<String, Value> = properties
+                    .into_iter()
+                    .flatten()
+                    .map(|(key, property)| (key.clone(), Self::example(property, root)))
+                    .collect();
+                Value::Object(object)
+            }
+            Some("array") => {
+                let items = schema.get("items").unwrap_or(&Value::Null);
+                let count = schema.get("minItems").and_then(Value::as_u64).unwrap_or(1).max(1);
+                Value::Array((0..count).map(|_| Self::example(items, root)).collect())
+            }
+            Some("string") if schema.get("format").and_then(Value::as_str) == Some("uuid") => {
+                json!(Uuid::nil())
+            }
+            Some("string") => json!("示例"),
+            Some("integer") => json!(minimum as i64),
+            Some("number") => json!(minimum),
+            Some("boolean") => json!(false),
+            _ => Value::Null,
+        }
+    }
+}
+
+#[async_trait]
+impl AiProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn embedding_model(&self) -> &str {
+        "mock-hash-64"
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> anyhow::Result<String> {
+        Ok(Self::reply(messages))
+    }
+
+    async fn chat_stream(&self, messages: &[ChatMessage]) -> anyhow::Result<DeltaStream> {
+        let chars: Vec<char> = Self::reply(messages).chars().collect();
+        let deltas: Vec<anyhow::Result<String>> = chars
+            .chunks(4)
+            .map(|chunk| Ok(chunk.iter().collect()))
+            .collect();
+
+        Ok(stream::iter(deltas).boxed())
+    }
+
+    async fn chat_json(&self, _messages: &[ChatMessage], _name: &str, schema: &Value) -> anyhow::Result<Value> {
+        Ok(Self::example(schema, schema))
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(inputs.iter().map(|input| Self::embedding(input)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use validator::Validate;
+
+    use super::*;
+    use crate::models::{
+        ModerationVerdict, PlanDocument, QuizDraft, MODERATION_VERDICT_SCHEMA, PLAN_DOCUMENT_SCHEMA,
+        QUIZ_DRAFT_SCHEMA,
+    };
+
+    #[tokio::test]
+    async fn chat_answers_the_last_user_message() {
+        let messages = [
+            ChatMessage::system("你是助教"),
+            ChatMessage::user("第一个问题"),
+            ChatMessage { role: ChatRole::Assistant, content: "第一个回答".to_string() },
+            ChatMessage::user("什么是所有权"),
+        ];
+        let reply = MockProvider.chat(&messages).await.unwrap();
+        assert!(reply.contains("「什么是所有权」"), "{}", reply);
+    }
+
+    #[tokio::test]
+    async fn chat_stream_concatenates_to_chat_reply() {
+        let messages = [ChatMessage::user("生命周期是什么")];
+        let deltas: Vec<String> = MockProvider.chat_stream(&messages).await.unwrap().try_collect().await.unwrap();
+
+        assert!(deltas.len() > 1);
+        assert!(deltas.iter().all(|delta| delta.chars().count() <= 4));
+        assert_eq!(deltas.concat(), MockProvider.chat(&messages).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn embeddings_are_deterministic_and_normalized() {
+        let inputs = vec!["Rust 所有权".to_string(), "rust 所有权".to_string(), "借用检查".to_string(), String::new()];
+        let vectors = MockProvider.embed(&inputs).await.unwrap();
+
+        assert_eq!(vectors.len(), inputs.len());
+        assert!(vectors.iter().all(|vector| vector.len() == MOCK_EMBEDDING_DIM));
+        assert_eq!(vectors[0], vectors[1]);
+        assert_ne!(vectors[0], vectors[2]);
+
+        let norm = vectors[0].iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!(vectors[3].iter().all(|v| *v == 0.0));
+    }
+
+    // 模拟的结构化输出必须能直接通过业务代码的反序列化和校验
+    #[tokio::test]
+    async fn chat_json_examples_satisfy_model_schemas() {
+        let quiz = MockProvider.chat_json(&[], "quiz", &QUIZ_DRAFT_SCHEMA).await.unwrap();
+        let quiz: QuizDraft = serde_json::from_value(quiz).unwrap();
+        quiz.validate().unwrap();
+
+        let plan = MockProvider.chat_json(&[], "study_plan", &PLAN_DOCUMENT_SCHEMA).await.unwrap();
+        let plan: PlanDocument = serde_json::from_value(plan).unwrap();
+        plan.validate().unwrap();
+
+        let verdict = MockProvider.chat_json(&[], "moderation", &MODERATION_VERDICT_SCHEMA).await.unwrap();
+        let verdict: ModerationVerdict = serde_json::from_value(verdict).unwrap();
+        assert!(!verdict.flagged);
+    }
+
+    #[test]
+    fn example_follows_refs_and_bounds() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "score": { "type": "integer", "minimum": 3, "maximum": 5 },
+                "ratio": { "type": ["number", "null"], "maximum": 0.5 },
+                "items": { "type": "array", "items": { "$ref": "#/$defs/Item" }, "minItems": 2 },
+            },
+            "$defs": { "Item": { "enum": ["first", "second"] } },
+        });
+
+        assert_eq!(
+            MockProvider::example(&schema, &schema),
+            json!({ "id": Uuid::nil(), "score": 3, "ratio": 0.5, "items": ["first", "first"] })
+        );
+    }
+}
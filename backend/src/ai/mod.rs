@@ -0,0 +1,94 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Secret;
+
+pub mod history;
+pub mod mock;
+pub mod moderation;
+pub mod openai;
+pub mod prompts;
+pub mod retrieval;
+pub mod usage;
+
+pub use mock::MockProvider;
+pub use openai::OpenAiProvider;
+pub use moderation::{Moderator, StreamModerator};
+pub use prompts::PromptRegistry;
+pub use retrieval::{Citation, ContentIndex, RetrievedChunk};
+pub use usage::{MeteredProvider, UsagePolicy};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self { role: ChatRole::System, content: content.into() }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: ChatRole::User, content: content.into() }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self { role: ChatRole::Assistant, content: content.into() }
+    }
+}
+
+// 流式回复，每一项是一段增量文本
+pub type DeltaStream = BoxStream<'static, anyhow::Result<String>>;
+
+#[async_trait]
+pub trait AiProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // 向量模型标识，不同模型生成的向量不能混用
+    fn embedding_model(&self) -> &str;
+
+    async fn chat(&self, messages: &[ChatMessage]) -> anyhow::Result<String>;
+
+    async fn chat_stream(&self, messages: &[ChatMessage]) -> anyhow::Result<DeltaStream>;
+
+    // 结构化输出：要求模型返回符合 schema 的 JSON，调用方仍需自行校验
+    async fn chat_json(&self, messages: &[ChatMessage], name: &str, schema: &Value) -> anyhow::Result<Value>;
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Vec<Vec<f32>>>;
+}
+
+pub type SharedAiProvider = Arc<dyn AiProvider>;
+
+#[derive(Debug, Clone)]
+pub struct AiConfig {
+    pub provider: String,
+    pub base_url: String,
+    pub api_key: Option<Secret>,
+    pub model: String,
+    pub embedding_model: String,
+    pub timeout: Duration,
+    // 是否在关键词/正则规则之外再用模型对输入输出做安全分类
+    pub moderation_classifier: bool,
+}
+
+pub fn build_provider(config: &AiConfig) -> anyhow::Result<SharedAiProvider> {
+    match config.provider.as_str() {
+        "openai" => Ok(Arc::new(OpenAiProvider::new(config)?)),
+        "mock" => Ok(Arc::new(MockProvider)),
+        other => anyhow::bail!("unknown AI provider: {}", other),
+    }
+}
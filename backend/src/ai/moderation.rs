@@ -0,0 +1,633 @@
+use std::{
+    ops::Range,
+    sync::{Arc, RwLock},
+};
+
+use regex::{Regex, RegexBuilder};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{ChatMessage, SharedAiProvider};
+use crate::database::Database;
+use crate::error::{AppError, ErrorCode};
+use crate::models::{
+    ModerationAction, ModerationRuleKind, ModerationSource, ModerationStage, ModerationVerdict,
+    NewModerationIncident, MODERATION_VERDICT_SCHEMA,
+};
+
+const REDACTION: &str = "***";
+// 事件记录中保存的片段长度上限
+const EXCERPT_CHARS: usize = 100;
+const MAX_PATTERN_SIZE: usize = 1 << 20;
+// 流式输出时末尾暂缓发送的最大字节数，长度没有上限的规则（如邮箱）按此计算；
+// RFC 5321 规定邮箱地址不超过 254 字节
+const MAX_HOLDBACK_BYTES: usize = 256;
+
+const CLASSIFIER_PROMPT: &str = "你是面向全年龄段学习平台的内容安全审核员。\
+判断用户提供的文本是否包含暴力、色情、自残、违法犯罪、仇恨歧视、辱骂骚扰等不适合未成年人的内容。\
+正常的学习讨论（包括历史、医学、文学中的相关知识）不算违规。只输出符合以下 JSON Schema 的 JSON：";
+
+fn rule_source(kind: ModerationRuleKind, pattern: &str) -> String {
+    match kind {
+        ModerationRuleKind::Keyword => regex::escape(pattern),
+        ModerationRuleKind::Regex => pattern.to_string(),
+    }
+}
+
+// 关键词按字面匹配，两类规则都不区分大小写
+pub fn compile_rule(kind: ModerationRuleKind, pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(&rule_source(kind, pattern))
+        .case_insensitive(true)
+        .size_limit(MAX_PATTERN_SIZE)
+        .build()
+}
+
+// 规则单次命中的最大字节数，没有上限时返回 None
+fn max_match_len(kind: ModerationRuleKind, pattern: &str) -> Option<usize> {
+    regex_syntax::ParserBuilder::new()
+        .case_insensitive(true)
+        .build()
+        .parse(&rule_source(kind, pattern))
+        .ok()?
+        .properties()
+        .maximum_len()
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    id: Uuid,
+    regex: Regex,
+    action: ModerationAction,
+    category: String,
+    max_len: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleHit {
+    pub rule_id: Uuid,
+    pub category: String,
+    pub excerpt: String,
+}
+
+// 脱敏规则在 text[from..] 中需要替换的区间。regex 不支持环视，边界条件写成命名分组 before / after，
+// 这两部分保持原样，after 匹配到的字符还可以作为下一处命中的 before
+fn redaction_spans(regex: &Regex, text: &str, from: usize) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut at = from;
+    while let Some(captures) = regex.captures_at(text, at) {
+        let found = captures.get(0).expect("group 0 always participates");
+        let start = captures.name("before").map_or(found.start(), |before| before.end());
+        let end = captures.name("after").map_or(found.end(), |after| after.start());
+        if start < end {
+            spans.push(start..end);
+        }
+
+        at = if end > found.start() {
+            end
+        } else {
+            // 空匹配时跳过一个字符，避免原地循环
+            match text[found.start()..].chars().next() {
+                Some(c) => found.start() + c.len_utf8(),
+                None => break,
+            }
+        };
+    }
+    spans
+}
+
+// 不同规则的命中区间可能重叠或相邻，合并后每段只替换为一个 REDACTION
+fn merge_spans(mut spans: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    spans.sort_by_key(|span| span.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(spans.len());
+    for span in spans {
+        match merged.last_mut() {
+            Some(last) if span.start <= last.end => last.end = last.end.max(span.end),
+            _ => merged.push(span),
+        }
+    }
+    merged
+}
+
+// 所有脱敏规则都在原文上从 from 开始匹配，返回合并后的命中区间
+fn find_redactions(rules: &[CompiledRule], text: &str, from: usize) -> (Vec<Range<usize>>, Vec<RuleHit>) {
+    let mut spans = Vec::new();
+    let mut hits = Vec::new();
+    for rule in rules.iter().filter(|rule| rule.action == ModerationAction::Redact) {
+        let found = redaction_spans(&rule.regex, text, from);
+        // 被脱敏的内容（如手机号）本身不写入事件记录
+        if !found.is_empty() {
+            hits.push(RuleHit {
+                rule_id: rule.id,
+                category: rule.category.clone(),
+                excerpt: REDACTION.to_string(),
+            });
+            spans.extend(found);
+        }
+    }
+    (merge_spans(spans), hits)
+}
+
+// 返回 text[from..] 脱敏后的文本，命中区间按 from 截断
+fn replace_spans(text: &str, spans: &[Range<usize>], from: usize) -> String {
+    let mut redacted = String::with_capacity(text.len() - from);
+    let mut copied = from;
+    for span in spans {
+        let start = span.start.max(from);
+        let end = span.end.min(text.len());
+        if start < end {
+            redacted.push_str(&text[copied..start]);
+            redacted.push_str(REDACTION);
+            copied = end;
+        }
+    }
+    redacted.push_str(&text[copied..]);
+    redacted
+}
+
+fn redact(rules: &[CompiledRule], text: &str) -> (String, Vec<RuleHit>) {
+    let (spans, hits) = find_redactions(rules, text, 0);
+    (replace_spans(text, &spans, 0), hits)
+}
+
+// text[from..] 中第一条命中的拦截规则，匹配时仍能看到 from 之前的上下文
+fn find_block(rules: &[CompiledRule], text: &str, from: usize) -> Option<RuleHit> {
+    rules
+        .iter()
+        .filter(|rule| rule.action == ModerationAction::Block)
+        .find_map(|rule| {
+            rule.regex.find_at(text, from).map(|found| RuleHit {
+                rule_id: rule.id,
+                category: rule.category.clone(),
+                excerpt: excerpt(found.as_str()),
+            })
+        })
+}
+
+// 单次命中可能的最大字节数，不小于任一拦截或脱敏规则的最长命中
+fn holdback(rules: &[CompiledRule]) -> usize {
+    rules
+        .iter()
+        .map(|rule| rule.max_len.unwrap_or(MAX_HOLDBACK_BYTES))
+        .max()
+        .unwrap_or(0)
+}
+
+// 不大于 at 的最近字符边界
+fn char_floor(text: &str, mut at: usize) -> usize {
+    while !text.is_char_boundary(at) {
+        at -= 1;
+    }
+    at
+}
+
+// 流式回答的审核。末尾始终保留不短于最长命中的一段：拦截规则在命中的任何部分发出之前就能发现，
+// 之后到达的内容也不会再与已发送的部分组成新的脱敏命中，因此每段输出都与完整回答脱敏后的结果一致。
+// 新内容只可能与最后这一段组成命中，每次只从这里开始重新匹配
+pub struct StreamModerator {
+    rules: Arc<RwLock<Vec<CompiledRule>>>,
+    // 分类器只能判断完整回答，启用时全部内容暂缓到结束后发送
+    hold_all: bool,
+    text: String,
+    released: usize,
+    // 拦截规则已经检查过的长度
+    scanned: usize,
+}
+
+impl StreamModerator {
+    // 目前收到的完整原文
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    // 追加一段原文，返回可以发送的脱敏文本（可能为空）；命中拦截规则时返回命中记录
+    pub fn push(&mut self, delta: &str) -> Result<String, RuleHit> {
+        self.text.push_str(delta);
+
+        let rules = Arc::clone(&self.rules);
+        let rules = rules.read().unwrap();
+        let from = char_floor(&self.text, self.scanned.saturating_sub(holdback(&rules)));
+        if let Some(hit) = find_block(&rules, &self.text, from) {
+            return Err(hit);
+        }
+        self.scanned = self.text.len();
+
+        if self.hold_all {
+            return Ok(String::new());
+        }
+        Ok(self.release(&rules, false))
+    }
+
+    // 上游结束并通过完整审核后返回剩余的全部文本
+    pub fn finish(&mut self) -> String {
+        let rules = Arc::clone(&self.rules);
+        let rules = rules.read().unwrap();
+        self.release(&rules, true)
+    }
+
+    fn release(&mut self, rules: &[CompiledRule], finished: bool) -> String {
+        let window = holdback(rules);
+        let mut cut = self.text.len();
+        if !finished {
+            cut = char_floor(&self.text, cut.saturating_sub(window));
+        }
+        if cut <= self.released {
+            return String::new();
+        }
+
+        let from = char_floor(&self.text, self.released.saturating_sub(window));
+        let (spans, _) = find_redactions(rules, &self.text, from);
+        // 跨过发送位置的命中整段留到下一次
+        if let Some(span) = spans.iter().find(|span| span.start < cut && span.end > cut) {
+            cut = span.start;
+        }
+        if cut <= self.released {
+            return String::new();
+        }
+
+        let released = replace_spans(&self.text[..cut], &spans, self.released);
+        self.released = cut;
+        released
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    text.chars().take(EXCERPT_CHARS).collect()
+}
+
+fn refusal(stage: ModerationStage) -> AppError {
+    match stage {
+        ModerationStage::Input => AppError::Coded(ErrorCode::InputRejected),
+        ModerationStage::Output => AppError::Coded(ErrorCode::OutputRejected),
+    }
+}
+
+// AI 调用前后的内容安全审核：先执行关键词/正则规则，再按配置调用模型分类器
+#[derive(Clone)]
+pub struct Moderator {
+    db: Database,
+    ai: SharedAiProvider,
+    classifier: bool,
+    rules: Arc<RwLock<Vec<CompiledRule>>>,
+}
+
+impl Moderator {
+    pub fn new(db: Database, ai: SharedAiProvider, classifier: bool) -> Self {
+        Self {
+            db,
+            ai,
+            classifier,
+            rules: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    pub async fn reload(&self) -> anyhow::Result<()> {
+        let rules = self
+            .db
+            .list_moderation_rules()
+            .await?
+            .into_iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match compile_rule(rule.kind, &rule.pattern) {
+                Ok(regex) => Some(CompiledRule {
+                    id: rule.id,
+                    regex,
+                    action: rule.action,
+                    max_len: max_match_len(rule.kind, &rule.pattern),
+                    category: rule.category,
+                }),
+                Err(err) => {
+                    tracing::warn!("skipping invalid moderation rule {}: {}", rule.id, err);
+                    None
+                }
+            })
+            .collect();
+
+        *self.rules.write().unwrap() = rules;
+        Ok(())
+    }
+
+    fn find_block(&self, text: &str) -> Option<RuleHit> {
+        find_block(&self.rules.read().unwrap(), text, 0)
+    }
+
+    fn redact(&self, text: &str) -> (String, Vec<RuleHit>) {
+        redact(&self.rules.read().unwrap(), text)
+    }
+
+    pub fn stream_moderator(&self) -> StreamModerator {
+        StreamModerator {
+            rules: self.rules.clone(),
+            hold_all: self.classifier,
+            text: String::new(),
+            released: 0,
+            scanned: 0,
+        }
+    }
+
+    // 分类器只是额外的一道防线，调用失败时放行并记录日志
+    async fn classify(&self, stage: ModerationStage, text: &str) -> Option<ModerationVerdict> {
+        let messages = vec![
+            ChatMessage::system(format!("{}\n{}", CLASSIFIER_PROMPT, *MODERATION_VERDICT_SCHEMA)),
+            ChatMessage::user(text),
+        ];
+        let verdict = self
+            .ai
+            .chat_json(&messages, "moderation", &MODERATION_VERDICT_SCHEMA)
+            .await
+            .and_then(|value| Ok(serde_json::from_value::<ModerationVerdict>(value)?))
+            .and_then(|verdict| {
+                verdict.validate()?;
+                Ok(verdict)
+            });
+
+        match verdict {
+            Ok(verdict) => Some(verdict),
+            Err(err) => {
+                tracing::warn!("moderation classifier failed on {:?}: {:?}", stage, err);
+                None
+            }
+        }
+    }
+
+    pub async fn record(&self, incident: NewModerationIncident) {
+        tracing::info!(
+            "moderation {:?} {:?} on {} ({}, {:?})",
+            incident.action,
+            incident.stage,
+            incident.feature,
+            incident.category,
+            incident.source
+        );
+        if let Err(err) = self.db.create_moderation_incident(&incident).await {
+            tracing::error!("failed to record moderation incident: {:?}", err);
+        }
+    }
+
+    pub async fn record_hit(
+        &self,
+        user_id: Option<Uuid>,
+        feature: &'static str,
+        stage: ModerationStage,
+        action: ModerationAction,
+        hit: RuleHit,
+    ) {
+        self.record(NewModerationIncident {
+            user_id,
+            feature,
+            stage,
+            action,
+            source: ModerationSource::Rule,
+            rule_id: Some(hit.rule_id),
+            category: hit.category,
+            excerpt: hit.excerpt,
+        })
+        .await;
+    }
+
+    // 审核一段文本：命中拦截规则或被分类器判定违规时返回拒绝错误，否则返回脱敏后的文本
+    pub async fn check(
+        &self,
+        user_id: Option<Uuid>,
+        feature: &'static str,
+        stage: ModerationStage,
+        text: &str,
+    ) -> Result<String, AppError> {
+        if let Some(hit) = self.find_block(text) {
+            self.record_hit(user_id, feature, stage, ModerationAction::Block, hit).await;
+            return Err(refusal(stage));
+        }
+
+        let (text, hits) = self.redact(text);
+        for hit in hits {
+            self.record_hit(user_id, feature, stage, ModerationAction::Redact, hit).await;
+        }
+
+        if self.classifier
+            && let Some(verdict) = self.classify(stage, &text).await
+            && verdict.flagged
+        {
+            self.record(NewModerationIncident {
+                user_id,
+                feature,
+                stage,
+                action: ModerationAction::Block,
+                source: ModerationSource::Classifier,
+                rule_id: None,
+                category: verdict.category,
+                excerpt: excerpt(&verdict.reason),
+            })
+            .await;
+            return Err(refusal(stage));
+        }
+
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID_NUMBER: &str = r"(?P<before>^|\D)\d{17}[\dXx](?P<after>\D|$)";
+    const PHONE: &str = r"(?P<before>^|\D)1[3-9]\d{9}(?P<after>\D|$)";
+    const EMAIL: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+
+    fn rule(kind: ModerationRuleKind, pattern: &str, action: ModerationAction) -> CompiledRule {
+        CompiledRule {
+            id: Uuid::new_v4(),
+            regex: compile_rule(kind, pattern).unwrap(),
+            action,
+            category: "privacy".to_string(),
+            max_len: max_match_len(kind, pattern),
+        }
+    }
+
+    fn privacy_rules() -> Vec<CompiledRule> {
+        [ID_NUMBER, PHONE, EMAIL]
+            .into_iter()
+            .map(|pattern| rule(ModerationRuleKind::Regex, pattern, ModerationAction::Redact))
+            .collect()
+    }
+
+    #[test]
+    fn redact_keeps_digit_boundaries() {
+        let rules = privacy_rules();
+        let cases = [
+            ("电话13800138000", "电话***"),
+            ("13800138000，请回电", "***，请回电"),
+            ("(13800138000)", "(***)"),
+            ("13800138000,13900139000", "***,***"),
+            ("身份证11010119900307123X。", "身份证***。"),
+            // 更长的数字串（订单号、时间戳）不是手机号或身份证号
+            ("订单号 213800138000", "订单号 213800138000"),
+            ("1380013800012345678901", "1380013800012345678901"),
+            ("邮箱 a.b@example.com 或 13800138000", "邮箱 *** 或 ***"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(redact(&rules, input).0, expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn redact_reports_each_rule_once() {
+        let (text, hits) = redact(&privacy_rules(), "13800138000 13900139000 a@b.cn");
+        assert_eq!(text, "*** *** ***");
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.excerpt == REDACTION));
+    }
+
+    #[test]
+    fn redact_merges_overlapping_hits() {
+        let rules = vec![
+            rule(ModerationRuleKind::Keyword, "secret", ModerationAction::Redact),
+            rule(ModerationRuleKind::Regex, r"cret\d+", ModerationAction::Redact),
+            rule(ModerationRuleKind::Keyword, "secret", ModerationAction::Block),
+        ];
+        assert_eq!(redact(&rules, "a SECRET42 b").0, "a *** b");
+    }
+
+    #[test]
+    fn redaction_spans_skip_empty_matches() {
+        let regex = compile_rule(ModerationRuleKind::Regex, r"x*").unwrap();
+        assert_eq!(redaction_spans(&regex, "axx好x", 0), vec![1..3, 6..7]);
+        assert_eq!(redaction_spans(&regex, "axx好x", 2), vec![2..3, 6..7]);
+    }
+
+    fn stream(rules: Vec<CompiledRule>, hold_all: bool) -> StreamModerator {
+        StreamModerator {
+            rules: Arc::new(RwLock::new(rules)),
+            hold_all,
+            text: String::new(),
+            released: 0,
+            scanned: 0,
+        }
+    }
+
+    #[test]
+    fn holdback_covers_longest_match_of_any_rule() {
+        let rules = privacy_rules();
+        // \d 和 \D 匹配 Unicode 字符，每个最长 4 字节
+        assert_eq!(rules[0].max_len, Some(20 * 4));
+        assert_eq!(rules[1].max_len, Some(2 + 11 * 4));
+        assert_eq!(rules[2].max_len, None);
+        assert_eq!(holdback(&rules), MAX_HOLDBACK_BYTES);
+        assert_eq!(holdback(&rules[..2]), 80);
+        assert_eq!(holdback(&[]), 0);
+
+        let block = rule(ModerationRuleKind::Regex, r"制作.{0,40}炸弹", ModerationAction::Block);
+        assert_eq!(block.max_len, Some(6 + 40 * 4 + 6));
+        let rules = vec![rule(ModerationRuleKind::Keyword, "炸弹", ModerationAction::Block), block];
+        assert_eq!(holdback(&rules), 172);
+    }
+
+    // 不论上游怎样切分，每段 delta 都不含脱敏规则命中的原文，拼接结果与完整回答脱敏后一致
+    #[test]
+    fn stream_deltas_never_contain_redacted_text() {
+        let rules = privacy_rules();
+        let secrets = ["13800138000", "13900139000", "11010119900307123X", "li.lei@example.com"];
+        let answer = format!(
+            "可以拨打{}或{}联系老师，报名需要身份证号{}。资料会发送到 {}，订单号 2024101813800 请保存。{}",
+            secrets[0],
+            secrets[1],
+            secrets[2],
+            secrets[3],
+            "后面是一段足够长的说明文字，确保在上游结束之前就有内容被发送出去。".repeat(10)
+        );
+        let (expected, _) = redact(&rules, &answer);
+        let chars: Vec<char> = answer.chars().collect();
+
+        for size in 1..=12 {
+            let mut redactor = stream(privacy_rules(), false);
+            let mut sent = String::new();
+            for chunk in chars.chunks(size) {
+                let delta = redactor.push(&chunk.iter().collect::<String>()).unwrap();
+                sent.push_str(&delta);
+                // 已发送的内容之后不会再改变，因此必须始终是最终结果的前缀
+                assert!(expected.starts_with(&sent), "chunk size {}: {:?}", size, sent);
+                assert!(
+                    secrets.iter().all(|secret| !delta.contains(secret)),
+                    "chunk size {}: {:?}",
+                    size,
+                    delta
+                );
+            }
+            assert!(!sent.is_empty(), "chunk size {}: nothing released before finish", size);
+            sent.push_str(&redactor.finish());
+            assert_eq!(sent, expected, "chunk size {}", size);
+        }
+        assert!(secrets.iter().all(|secret| !expected.contains(secret)));
+    }
+
+    // 拦截规则的命中被切分到多段 delta 中时，命中的任何部分都不会在中止之前发出
+    #[test]
+    fn stream_blocks_before_releasing_any_part_of_a_split_hit() {
+        let rules = || {
+            let mut rules = privacy_rules();
+            rules.push(rule(ModerationRuleKind::Keyword, "炸弹", ModerationAction::Block));
+            rules.push(rule(ModerationRuleKind::Regex, r"制作.{0,10}燃烧瓶", ModerationAction::Block));
+            rules
+        };
+        let prefix = "这是一段足够长的化学实验安全说明，".repeat(12);
+        for (answer, phrase) in [
+            (format!("{}不要尝试自制炸弹，", prefix), "炸弹"),
+            (format!("{}网上流传的制作简易燃烧瓶的方法", prefix), "制作简易燃烧瓶"),
+        ] {
+            let chars: Vec<char> = answer.chars().collect();
+            let phrase_end = answer.find(phrase).unwrap() + phrase.len();
+
+            for size in 1..=8 {
+                let mut moderator = stream(rules(), false);
+                let mut sent = String::new();
+                let mut received = 0;
+                let mut blocked = None;
+                for chunk in chars.chunks(size) {
+                    let delta: String = chunk.iter().collect();
+                    received += delta.len();
+                    match moderator.push(&delta) {
+                        Ok(released) => sent.push_str(&released),
+                        Err(hit) => {
+                            blocked = Some(hit);
+                            break;
+                        }
+                    }
+                }
+
+                let hit = blocked.unwrap_or_else(|| panic!("chunk size {}: {:?} was not blocked", size, phrase));
+                assert_eq!(hit.excerpt, phrase);
+                // 在收到命中的最后一个字符时中止，此前发出的内容不含命中的任何字符
+                assert!(received >= phrase_end && received < phrase_end + size * 4, "chunk size {}", size);
+                assert!(!sent.is_empty(), "chunk size {}", size);
+                assert!(answer.starts_with(&sent) && sent.len() <= answer.find(phrase).unwrap(), "chunk size {}: {:?}", size, sent);
+            }
+        }
+    }
+
+    #[test]
+    fn stream_holds_back_only_the_longest_match() {
+        let mut moderator = stream(Vec::new(), false);
+        assert_eq!(moderator.push("电话").unwrap(), "电话");
+        assert_eq!(moderator.push("13800138000").unwrap(), "13800138000");
+        assert_eq!(moderator.finish(), "");
+
+        // 「炸弹」为 6 字节，末尾两个汉字暂缓发送
+        let mut moderator = stream(vec![rule(ModerationRuleKind::Keyword, "炸弹", ModerationAction::Block)], false);
+        assert_eq!(moderator.push("电话").unwrap(), "");
+        assert_eq!(moderator.push("号码是").unwrap(), "电话号");
+        assert_eq!(moderator.finish(), "码是");
+        assert_eq!(moderator.text(), "电话号码是");
+    }
+
+    #[test]
+    fn stream_holds_everything_when_the_classifier_is_enabled() {
+        let mut moderator = stream(privacy_rules(), true);
+        let answer = "足够长的回答内容。".repeat(40) + "电话13800138000";
+        for c in answer.chars() {
+            assert_eq!(moderator.push(&c.to_string()).unwrap(), "");
+        }
+        assert_eq!(moderator.finish(), redact(&privacy_rules(), &answer).0);
+
+        let mut moderator = stream(vec![rule(ModerationRuleKind::Keyword, "炸弹", ModerationAction::Block)], true);
+        assert_eq!(moderator.push("炸").unwrap(), "");
+        assert!(moderator.push("弹").is_err());
+    }
+}
@@ -0,0 +1,460 @@
+use std::{collections::VecDeque, time::Duration};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use axum::body::Bytes;
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{AiConfig, AiProvider, ChatMessage, DeltaStream};
+
+// 兼容 OpenAI Chat Completions / Embeddings 接口的服务（OpenAI、vLLM、Ollama 等）
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    embedding_model: String,
+    timeout: Duration,
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+    choices: Vec<CompletionChoice>,
+}
+
+#[derive(Deserialize)]
+struct CompletionChoice {
+    message: CompletionMessage,
+}
+
+#[derive(Deserialize)]
+struct CompletionMessage {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Deserialize)]
+struct StreamDelta {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiProvider {
+    pub fn new(config: &AiConfig) -> anyhow::Result<Self> {
+        // 客户端只限制建立连接和两次读取之间的空闲时间；整体超时按请求设置，
+        // 流式回答的总时长不受限制，只要上游持续输出就不会被中断
+        let client = reqwest::Client::builder()
+            .connect_timeout(config.timeout)
+            .read_timeout(config.timeout)
+            .build()
+            .context("failed to build AI http client")?;
+
+        Ok(Self {
+            client,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            api_key: config.api_key.as_ref().map(|key| key.expose().to_string()),
+            model: config.model.clone(),
+            embedding_model: config.embedding_model.clone(),
+            timeout: config.timeout,
+        })
+    }
+
+    // 非流式请求从连接开始到读完响应体整体不超过 timeout，流式请求传 None
+    async fn post(
+        &self,
+        path: &str,
+        body: serde_json::Value,
+        timeout: Option<Duration>,
+    ) -> anyhow::Result<reqwest::Response> {
+        let mut request = self.client.post(format!("{}{}", self.base_url, path)).json(&body);
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await.context("AI provider request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let body: String = body.chars().take(500).collect();
+            anyhow::bail!("AI provider returned {}: {}", status, body);
+        }
+
+        Ok(response)
+    }
+}
+
+// 不支持 response_format 的兼容服务可能用代码块包裹 JSON，取最外层的对象解析
+fn parse_json_reply(content: &str) -> anyhow::Result<Value> {
+    let start = content.find('{').context("model reply contains no JSON object")?;
+    let end = content.rfind('}').context("model reply contains no JSON object")?;
+    anyhow::ensure!(start < end, "model reply contains no JSON object");
+
+    serde_json::from_str(&content[start..=end]).context("model reply is not valid JSON")
+}
+
+// SSE 解析状态：未消费完的字节、已解析出的增量文本，以及是否已收到 [DONE]
+struct SseState {
+    bytes: stream::BoxStream<'static, reqwest::Result<Bytes>>,
+    buffer: Vec<u8>,
+    pending: VecDeque<String>,
+    done: bool,
+}
+
+impl SseState {
+    fn drain_lines(&mut self) -> anyhow::Result<()> {
+        // 按完整的行解码，避免多字节字符被拆在两个数据块之间
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim_start();
+            if data == "[DONE]" {
+                self.done = true;
+                return Ok(());
+            }
+
+            let chunk: StreamChunk = serde_json::from_str(data)
+                .with_context(|| format!("invalid stream chunk: {}", data))?;
+            if let Some(content) = chunk.choices.into_iter().next().and_then(|c| c.delta.content)
+                && !content.is_empty()
+            {
+                self.pending.push_back(content);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AiProvider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn embedding_model(&self) -> &str {
+        &self.embedding_model
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> anyhow::Result<String> {
+        let response: CompletionResponse = self
+            .post(
+                "/chat/completions",
+                json!({ "model": self.model, "messages": messages }),
+                Some(self.timeout),
+            )
+            .await?
+            .json()
+            .await
+            .context("invalid chat completion response")?;
+
+        response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .context("chat completion response has no content")
+    }
+
+    async fn chat_json(&self, messages: &[ChatMessage], name: &str, schema: &Value) -> anyhow::Result<Value> {
+        let body = json!({
+            "model": self.model,
+            "messages": messages,
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": { "name": name, "schema": schema },
+            },
+        });
+        let response: CompletionResponse = self
+            .post("/chat/completions", body, Some(self.timeout))
+            .await?
+            .json()
+            .await
+            .context("invalid chat completion response")?;
+
+        let content = response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .context("chat completion response has no content")?;
+
+        parse_json_reply(&content)
+    }
+
+    // 响应流被丢弃时底层连接随之关闭，上游生成也会停止
+    async fn chat_stream(&self, messages: &[ChatMessage]) -> anyhow::Result<DeltaStream> {
+        let response = self
+            .post(
+                "/chat/completions",
+                json!({ "model": self.model, "messages": messages, "stream": true }),
+                None,
+            )
+            .await?;
+
+        let state = SseState {
+            bytes: response.bytes_stream().boxed(),
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+            done: false,
+        };
+
+        let deltas = stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some(delta) = state.pending.pop_front() {
+                    return Ok(Some((delta, state)));
+                }
+                if state.done {
+                    return Ok(None);
+                }
+                match state.bytes.next().await {
+                    Some(chunk) => {
+                        let chunk = chunk.context("AI provider stream interrupted")?;
+                        state.buffer.extend_from_slice(&chunk);
+                        state.drain_lines()?;
+                    }
+                    None => {
+                        state.buffer.push(b'\n');
+                        state.drain_lines()?;
+                        state.done = true;
+                    }
+                }
+            }
+        });
+
+        Ok(deltas.boxed())
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut response: EmbeddingResponse = self
+            .post(
+                "/embeddings",
+                json!({ "model": self.embedding_model, "input": inputs }),
+                Some(self.timeout),
+            )
+            .await?
+            .json()
+            .await
+            .context("invalid embedding response")?;
+
+        response.data.sort_by_key(|data| data.index);
+        anyhow::ensure!(
+            response.data.len() == inputs.len(),
+            "expected {} embeddings, got {}",
+            inputs.len(),
+            response.data.len()
+        );
+
+        Ok(response.data.into_iter().map(|data| data.embedding).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, time::Duration};
+
+    use axum::{
+        body::Body,
+        http::HeaderMap,
+        response::{IntoResponse, Response},
+        routing::post,
+        Json, Router,
+    };
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::config::Secret;
+
+    fn sse_state() -> SseState {
+        SseState {
+            bytes: stream::empty().boxed(),
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    fn chunk(content: &str) -> String {
+        format!("data: {}\n\n", json!({ "choices": [{ "delta": { "content": content } }] }))
+    }
+
+    #[test]
+    fn drain_lines_collects_deltas_and_skips_other_lines() {
+        let mut state = sse_state();
+        let input = format!(
+            ": keep-alive\nevent: message\n{}{}data: {}\n\n",
+            chunk("你好"),
+            chunk(""),
+            json!({ "choices": [{ "delta": { "role": "assistant" } }] })
+        );
+        state.buffer.extend_from_slice(input.as_bytes());
+        state.drain_lines().unwrap();
+
+        assert_eq!(state.pending, ["你好"]);
+        assert!(!state.done);
+        assert!(state.buffer.is_empty());
+    }
+
+    #[test]
+    fn drain_lines_waits_for_complete_lines() {
+        let mut state = sse_state();
+        let bytes = chunk("所有权").into_bytes();
+        // 在“所”字的 UTF-8 编码中间切开
+        let split = bytes.iter().position(|&b| b >= 0x80).unwrap() + 1;
+
+        state.buffer.extend_from_slice(&bytes[..split]);
+        state.drain_lines().unwrap();
+        assert!(state.pending.is_empty());
+
+        state.buffer.extend_from_slice(&bytes[split..]);
+        state.drain_lines().unwrap();
+        assert_eq!(state.pending, ["所有权"]);
+    }
+
+    #[test]
+    fn drain_lines_stops_at_done() {
+        let mut state = sse_state();
+        let input = format!("{}data: [DONE]\n\n{}", chunk("a"), chunk("b"));
+        state.buffer.extend_from_slice(input.as_bytes());
+        state.drain_lines().unwrap();
+
+        assert_eq!(state.pending, ["a"]);
+        assert!(state.done);
+    }
+
+    #[test]
+    fn drain_lines_rejects_invalid_chunks() {
+        let mut state = sse_state();
+        state.buffer.extend_from_slice(b"data: {not json}\n");
+        assert!(state.drain_lines().is_err());
+    }
+
+    #[test]
+    fn parse_json_reply_accepts_bare_and_fenced_objects() {
+        assert_eq!(parse_json_reply(r#"{"a": 1}"#).unwrap(), json!({ "a": 1 }));
+        assert_eq!(
+            parse_json_reply("好的：\n```json\n{\"a\": {\"b\": [1, 2]}}\n```").unwrap(),
+            json!({ "a": { "b": [1, 2] } })
+        );
+    }
+
+    #[test]
+    fn parse_json_reply_rejects_replies_without_an_object() {
+        assert!(parse_json_reply("抱歉，我无法生成").is_err());
+        assert!(parse_json_reply("} {").is_err());
+        assert!(parse_json_reply("{\"a\": }").is_err());
+    }
+
+    // 本地模拟的 OpenAI 兼容服务：回显最后一条消息，流式回答逐字输出
+    async fn chat_completions(headers: HeaderMap, Json(body): Json<Value>) -> Response {
+        assert_eq!(headers["authorization"], "Bearer test-key");
+        assert_eq!(body["model"], "test-model");
+        let answer = format!("回答：{}", body["messages"][0]["content"].as_str().unwrap());
+
+        if body["stream"] == json!(true) {
+            let mut events: Vec<Result<Bytes, Infallible>> =
+                answer.chars().map(|c| Ok(Bytes::from(chunk(&c.to_string())))).collect();
+            events.push(Ok(Bytes::from_static(b"data: [DONE]\n\n")));
+            return ([("content-type", "text/event-stream")], Body::from_stream(stream::iter(events)))
+                .into_response();
+        }
+
+        Json(json!({ "choices": [{ "message": { "role": "assistant", "content": answer } }] })).into_response()
+    }
+
+    async fn embeddings(Json(body): Json<Value>) -> Json<Value> {
+        assert_eq!(body["model"], "test-embedding");
+        let data: Vec<Value> = body["input"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(index, input)| json!({ "index": index, "embedding": [input.as_str().unwrap().len() as f32] }))
+            .rev()
+            .collect();
+        Json(json!({ "data": data }))
+    }
+
+    async fn provider() -> OpenAiProvider {
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/embeddings", post(embeddings));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        OpenAiProvider::new(&AiConfig {
+            provider: "openai".to_string(),
+            base_url: format!("http://{}/v1/", address),
+            api_key: Some("test-key".parse::<Secret>().unwrap()),
+            model: "test-model".to_string(),
+            embedding_model: "test-embedding".to_string(),
+            timeout: Duration::from_secs(5),
+            moderation_classifier: false,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn chat_returns_message_content() {
+        let provider = provider().await;
+        let reply = provider.chat(&[ChatMessage::user("什么是借用")]).await.unwrap();
+        assert_eq!(reply, "回答：什么是借用");
+    }
+
+    #[tokio::test]
+    async fn chat_stream_yields_deltas_in_order() {
+        let provider = provider().await;
+        let deltas: Vec<String> = provider
+            .chat_stream(&[ChatMessage::user("生命周期")])
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(deltas.len(), "回答：生命周期".chars().count());
+        assert_eq!(deltas.concat(), "回答：生命周期");
+    }
+
+    #[tokio::test]
+    async fn embed_orders_vectors_by_index() {
+        let provider = provider().await;
+        let inputs = vec!["a".to_string(), "bbb".to_string(), "cc".to_string()];
+        let vectors = provider.embed(&inputs).await.unwrap();
+        assert_eq!(vectors, vec![vec![1.0], vec![3.0], vec![2.0]]);
+    }
+
+    #[tokio::test]
+    async fn error_status_is_reported() {
+        let mut provider = provider().await;
+        provider.base_url.push_str("/missing");
+        let error = provider.chat(&[ChatMessage::user("hi")]).await.unwrap_err();
+        assert!(error.to_string().contains("404"), "{}", error);
+    }
+}
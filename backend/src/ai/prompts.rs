@@ -0,0 +1,257 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use rand::Rng;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::usage;
+use crate::database::Database;
+use crate::models::{PromptTemplate, PromptVersion};
+
+pub const SYSTEM_PROMPT: &str = "你是学乐无穷学习平台的AI助教，请用简洁、准确、循序渐进的方式回答学习者的问题。";
+
+// 可配置的提示词：名称、可用变量，以及数据库中没有启用版本时使用的内置模板
+#[derive(Debug)]
+pub struct PromptSpec {
+    pub name: &'static str,
+    pub variables: &'static [&'static str],
+    pub builtin: &'static str,
+}
+
+pub const CHAT_PROMPT: PromptSpec = PromptSpec {
+    name: "chat",
+    variables: &[],
+    builtin: SYSTEM_PROMPT,
+};
+
+pub const STUDY_PLAN_PROMPT: PromptSpec = PromptSpec {
+    name: "study_plan",
+    variables: &["subject", "level", "goals", "weeks", "hours_per_week", "schema"],
+    builtin: "请为学习者制定分阶段的学习计划：划分若干里程碑，按周安排具体任务并估算每个任务的学时。\
+如果平台内容与某个任务相关，把它的 content_id 填入该任务的 content_ids。只输出符合以下 JSON Schema 的 JSON：\n\
+{{schema}}\n\n\
+学习主题：{{subject}}\n当前水平：{{level}}\n学习目标：{{goals}}\n学习周期：{{weeks}}\n每周可投入：{{hours_per_week}}",
+};
+
+pub const QUIZ_PROMPT: PromptSpec = PromptSpec {
+    name: "quiz",
+    variables: &["multiple_choice", "short_answer", "title", "body", "schema"],
+    builtin: "请根据下面的学习内容出一套测验，检查学习者是否理解了要点。\
+选择题给出 2 到 6 个选项，并用 correct_option 标明正确选项的下标（从 0 开始）；\
+简答题给出 reference_answer 和用于评分的 keywords。每道题附上简短的 explanation。只输出符合以下 JSON Schema 的 JSON：\n\
+{{schema}}\n\n\
+请出 {{multiple_choice}} 道选择题和 {{short_answer}} 道简答题。\n\n《{{title}}》\n{{body}}",
+};
+
+pub const SUGGESTION_PROMPT: PromptSpec = PromptSpec {
+    name: "suggestions",
+    variables: &["interests", "picks"],
+    builtin: "下面是系统为学习者挑选的推荐内容及推荐依据，请用两三句话向学习者说明为什么推荐这些内容，以及建议的学习顺序。\n\n\
+兴趣：{{interests}}\n推荐内容：\n{{picks}}",
+};
+
+pub const PROMPTS: [&PromptSpec; 4] = [&CHAT_PROMPT, &STUDY_PLAN_PROMPT, &QUIZ_PROMPT, &SUGGESTION_PROMPT];
+
+pub fn find_spec(name: &str) -> Option<&'static PromptSpec> {
+    PROMPTS.into_iter().find(|spec| spec.name == name)
+}
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").expect("invalid placeholder regex"));
+
+// 模板中使用了但该提示词不支持的变量
+pub fn unknown_variables(spec: &PromptSpec, template: &str) -> Vec<String> {
+    let mut unknown: Vec<String> = PLACEHOLDER
+        .captures_iter(template)
+        .map(|captures| captures[1].to_string())
+        .filter(|name| !spec.variables.contains(&name.as_str()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    unknown.sort();
+    unknown
+}
+
+// 变量值只替换一次，值中出现的 {{...}} 不会再被展开
+fn render_template(template: &str, variables: &[(&str, String)]) -> String {
+    PLACEHOLDER
+        .replace_all(template, |captures: &regex::Captures| {
+            variables
+                .iter()
+                .find(|(name, _)| *name == &captures[1])
+                .map(|(_, value)| value.clone())
+                .unwrap_or_default()
+        })
+        .into_owned()
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderedPrompt {
+    pub text: String,
+    pub version: PromptVersion,
+}
+
+#[derive(Debug, Clone)]
+struct ActiveTemplate {
+    version: i32,
+    template: String,
+    weight: u32,
+}
+
+// 按权重在启用的版本中选择；登录用户按用户 ID 固定分组，同一用户始终看到同一个版本
+fn choose<'a>(versions: &'a [ActiveTemplate], name: &str, user_id: Option<Uuid>) -> &'a ActiveTemplate {
+    let total: u32 = versions.iter().map(|template| template.weight).sum();
+    let mut point = match user_id {
+        Some(user_id) => {
+            let digest = Sha256::digest(format!("{}:{}", name, user_id));
+            u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) % total
+        }
+        None => rand::thread_rng().gen_range(0..total),
+    };
+    for template in versions {
+        if point < template.weight {
+            return template;
+        }
+        point -= template.weight;
+    }
+
+    unreachable!("selection point is below the total weight")
+}
+
+// 启用中的提示词版本缓存在内存中，管理接口修改后以及定时任务会重新载入
+#[derive(Clone)]
+pub struct PromptRegistry {
+    db: Database,
+    active: Arc<RwLock<HashMap<String, Vec<ActiveTemplate>>>>,
+}
+
+impl PromptRegistry {
+    pub fn new(db: Database) -> Self {
+        Self {
+            db,
+            active: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn reload(&self) -> anyhow::Result<()> {
+        let templates = self.db.get_active_prompt_templates().await?;
+        let mut active: HashMap<String, Vec<ActiveTemplate>> = HashMap::new();
+        for PromptTemplate { name, version, template, weight, .. } in templates {
+            active.entry(name).or_default().push(ActiveTemplate {
+                version,
+                template,
+                weight: weight as u32,
+            });
+        }
+
+        *self.active.write().unwrap() = active;
+        Ok(())
+    }
+
+    fn select(&self, spec: &PromptSpec, user_id: Option<Uuid>) -> (i32, String) {
+        let active = self.active.read().unwrap();
+        match active.get(spec.name).filter(|versions| !versions.is_empty()) {
+            Some(versions) => {
+                let template = choose(versions, spec.name, user_id);
+                (template.version, template.template.clone())
+            }
+            None => (0, spec.builtin.to_string()),
+        }
+    }
+
+    // 渲染选中的版本，并把版本号记入本次请求的 AI 用量
+    pub fn render(&self, spec: &PromptSpec, user_id: Option<Uuid>, variables: &[(&str, String)]) -> RenderedPrompt {
+        let (version, template) = self.select(spec, user_id);
+        let version = PromptVersion { name: spec.name.to_string(), version };
+        usage::record_prompt(&version);
+
+        RenderedPrompt {
+            text: render_template(&template, variables),
+            version,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(weights: &[u32]) -> Vec<ActiveTemplate> {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(i, &weight)| ActiveTemplate {
+                version: i as i32 + 1,
+                template: format!("v{}", i + 1),
+                weight,
+            })
+            .collect()
+    }
+
+    fn share(counts: &HashMap<i32, u32>, version: i32, total: u32) -> f64 {
+        counts.get(&version).copied().unwrap_or_default() as f64 / total as f64
+    }
+
+    #[test]
+    fn logged_in_users_stick_to_one_version() {
+        let versions = versions(&[50, 50]);
+        for _ in 0..20 {
+            let user_id = Uuid::new_v4();
+            let first = choose(&versions, "chat", Some(user_id)).version;
+            assert!((0..10).all(|_| choose(&versions, "chat", Some(user_id)).version == first));
+        }
+    }
+
+    #[test]
+    fn selection_follows_weights() {
+        let versions = versions(&[80, 20, 0]);
+        let total = 20_000;
+
+        let mut by_user: HashMap<i32, u32> = HashMap::new();
+        let mut anonymous: HashMap<i32, u32> = HashMap::new();
+        for i in 0..total {
+            *by_user.entry(choose(&versions, "chat", Some(Uuid::from_u128(i as u128))).version).or_default() += 1;
+            *anonymous.entry(choose(&versions, "chat", None).version).or_default() += 1;
+        }
+
+        for counts in [&by_user, &anonymous] {
+            assert!((share(counts, 1, total) - 0.8).abs() < 0.02, "{:?}", counts);
+            assert!((share(counts, 2, total) - 0.2).abs() < 0.02, "{:?}", counts);
+            assert!(!counts.contains_key(&3), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn render_substitutes_every_variable() {
+        let rendered = render_template(
+            "主题：{{subject}}，水平：{{ level }}，再提一次 {{subject}}，缺省：[{{weeks}}]",
+            &[("subject", "Rust {{level}}".to_string()), ("level", "入门".to_string())],
+        );
+        // 变量值中的 {{...}} 不再展开，未提供的变量替换为空
+        assert_eq!(rendered, "主题：Rust {{level}}，水平：入门，再提一次 Rust {{level}}，缺省：[]");
+
+        let variables: Vec<(&str, String)> = STUDY_PLAN_PROMPT
+            .variables
+            .iter()
+            .map(|name| (*name, format!("<{}>", name)))
+            .collect();
+        let rendered = render_template(STUDY_PLAN_PROMPT.builtin, &variables);
+        assert!(!rendered.contains("{{"), "{}", rendered);
+        assert!(rendered.contains("学习主题：<subject>"));
+    }
+
+    #[test]
+    fn unknown_variables_are_reported_once_and_sorted() {
+        let template = "{{subject}} {{ topic }} {{level}} {{topic}} {{Audience}} {{ not closed";
+        assert_eq!(unknown_variables(&STUDY_PLAN_PROMPT, template), vec!["Audience", "topic"]);
+        assert!(unknown_variables(&CHAT_PROMPT, "no placeholders").is_empty());
+        assert_eq!(unknown_variables(&CHAT_PROMPT, "{{subject}}"), vec!["subject"]);
+        for spec in PROMPTS {
+            assert!(unknown_variables(spec, spec.builtin).is_empty(), "{}", spec.name);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::SharedAiProvider;
+use crate::database::Database;
+use crate::models::{Content, ContentChunk};
+
+// 每个段落的最大字符数，以及超长段落按窗口切分时相邻窗口的重叠字符数
+const MAX_CHUNK_CHARS: usize = 800;
+const CHUNK_OVERLAP: usize = 100;
+const SNIPPET_CHARS: usize = 160;
+// 相似度低于该值的段落不作为参考资料
+const MIN_SCORE: f32 = 0.2;
+
+pub const RETRIEVAL_TOP_K: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Citation {
+    pub content_id: Uuid,
+    pub title: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RetrievedChunk {
+    pub content_id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub score: f32,
+}
+
+impl RetrievedChunk {
+    pub fn citation(&self) -> Citation {
+        let mut snippet: String = self.body.chars().take(SNIPPET_CHARS).collect();
+        if self.body.chars().count() > SNIPPET_CHARS {
+            snippet.push('…');
+        }
+
+        Citation {
+            content_id: self.content_id,
+            title: self.title.clone(),
+            snippet,
+        }
+    }
+}
+
+// 先按空行分段并合并到上限以内，超长段落再按固定窗口切分
+pub fn chunk_text(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        let length = paragraph.chars().count();
+        if !current.is_empty() && current.chars().count() + length + 1 > MAX_CHUNK_CHARS {
+            chunks.push(std::mem::take(&mut current));
+        }
+
+        if length > MAX_CHUNK_CHARS {
+            let chars: Vec<char> = paragraph.chars().collect();
+            let mut start = 0;
+            loop {
+                let end = (start + MAX_CHUNK_CHARS).min(chars.len());
+                chunks.push(chars[start..end].iter().collect());
+                if end == chars.len() {
+                    break;
+                }
+                start += MAX_CHUNK_CHARS - CHUNK_OVERLAP;
+            }
+            continue;
+        }
+
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(paragraph);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+// 内容向量索引：向量持久化在 content_chunks 表中，检索时在内存里做余弦相似度计算
+#[derive(Clone)]
+pub struct ContentIndex {
+    ai: SharedAiProvider,
+    db: Database,
+    chunks: Arc<RwLock<HashMap<Uuid, Vec<ContentChunk>>>>,
+    // 正在建立索引的内容；值为 true 表示期间内容又有变化，需要按最新内容再索引一次
+    indexing: Arc<Mutex<HashMap<Uuid, bool>>>,
+}
+
+impl ContentIndex {
+    pub fn new(ai: SharedAiProvider, db: Database) -> Self {
+        Self {
+            ai,
+            db,
+            chunks: Arc::new(RwLock::new(HashMap::new())),
+            indexing: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // 启动时载入已有向量，再为尚未用当前模型建立索引的内容补建索引
+    pub async fn load(&self) -> anyhow::Result<()> {
+        let model = self.ai.embedding_model().to_string();
+        let stored = self.db.get_content_chunks(&model).await?;
+        {
+            let mut chunks = self.chunks.write().expect("content index lock poisoned");
+            for chunk in stored {
+                chunks.entry(chunk.content_id).or_default().push(chunk);
+            }
+        }
+
+        let pending = self.db.get_unindexed_contents(&model).await?;
+        if !pending.is_empty() {
+            tracing::info!("indexing {} contents for retrieval", pending.len());
+        }
+        for content in pending {
+            if self.claim(content.id) {
+                self.index_latest(content.id).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn index_content(&self, content: &Content) -> anyhow::Result<()> {
+        let bodies = chunk_text(&content.body);
+        // 把标题拼进待向量化的文本，提高按主题检索的命中率
+        let inputs: Vec<String> = bodies
+            .iter()
+            .map(|body| format!("{}\n{}", content.title, body))
+            .collect();
+        let embeddings = if inputs.is_empty() {
+            Vec::new()
+        } else {
+            self.ai.embed(&inputs).await?
+        };
+
+        let rows: Vec<(String, Vec<f32>)> = bodies.into_iter().zip(embeddings).collect();
+        self.db
+            .replace_content_chunks(content.id, self.ai.embedding_model(), &rows)
+            .await?;
+
+        let indexed = rows
+            .into_iter()
+            .map(|(body, embedding)| ContentChunk {
+                content_id: content.id,
+                title: content.title.clone(),
+                body,
+                embedding,
+            })
+            .collect();
+        self.chunks
+            .write()
+            .expect("content index lock poisoned")
+            .insert(content.id, indexed);
+        Ok(())
+    }
+
+    // 在后台建立索引，向量服务出错不影响内容本身的写入
+    pub fn spawn_index(&self, content_id: Uuid) {
+        if self.claim(content_id) {
+            let index = self.clone();
+            tokio::spawn(async move { index.index_latest(content_id).await });
+        }
+    }
+
+    pub fn remove(&self, content_id: Uuid) {
+        self.chunks
+            .write()
+            .expect("content index lock poisoned")
+            .remove(&content_id);
+        // 正在进行的索引任务可能在此之后写回向量，让它再读一次数据库
+        if let Some(dirty) = self.indexing.lock().expect("content index lock poisoned").get_mut(&content_id) {
+            *dirty = true;
+        }
+    }
+
+    // 同一内容同时只允许一个索引任务；已有任务时只做标记，由该任务结束前再索引一次
+    fn claim(&self, content_id: Uuid) -> bool {
+        let mut indexing = self.indexing.lock().expect("content index lock poisoned");
+        match indexing.get_mut(&content_id) {
+            Some(dirty) => {
+                *dirty = true;
+                false
+            }
+            None => {
+                indexing.insert(content_id, false);
+                true
+            }
+        }
+    }
+
+    // 每轮都从数据库读取最新的内容，旧正文的向量不会覆盖新正文的向量
+    async fn index_latest(&self, content_id: Uuid) {
+        loop {
+            let result = match self.db.get_content_by_id(content_id).await {
+                Ok(Some(content)) => self.index_content(&content).await,
+                Ok(None) => {
+                    self.chunks
+                        .write()
+                        .expect("content index lock poisoned")
+                        .remove(&content_id);
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                tracing::warn!("failed to index content {}: {:?}", content_id, err);
+            }
+
+            let mut indexing = self.indexing.lock().expect("content index lock poisoned");
+            match indexing.get_mut(&content_id) {
+                Some(dirty) if *dirty => *dirty = false,
+                _ => {
+                    indexing.remove(&content_id);
+                    return;
+                }
+            }
+        }
+    }
+
+    pub async fn search(&self, query: &str, top_k: usize) -> anyhow::Result<Vec<RetrievedChunk>> {
+        let query_embedding = self
+            .ai
+            .embed(&[query.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+
+        let chunks = self.chunks.read().expect("content index lock poisoned");
+        let mut hits: Vec<RetrievedChunk> = chunks
+            .values()
+            .flatten()
+            .filter_map(|chunk| {
+                let score = cosine_similarity(&query_embedding, &chunk.embedding);
+                (score >= MIN_SCORE).then(|| RetrievedChunk {
+                    content_id: chunk.content_id,
+                    title: chunk.title.clone(),
+                    body: chunk.body.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(top_k);
+        Ok(hits)
+    }
+}
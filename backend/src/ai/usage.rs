@@ -0,0 +1,414 @@
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicI64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration as StdDuration,
+};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::TryStreamExt;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::history::estimate_tokens;
+use super::{AiProvider, ChatMessage, DeltaStream, SharedAiProvider};
+use crate::auth::CurrentUser;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::models::{PromptVersion, UsageWindow, UserRole};
+use crate::state::AppState;
+
+// 单个角色的调用限制，None 表示该项不限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub requests_per_day: Option<i64>,
+    pub tokens_per_day: Option<i64>,
+    pub requests_per_minute: Option<i64>,
+}
+
+impl Quota {
+    const UNLIMITED: Quota = Quota {
+        requests_per_day: None,
+        tokens_per_day: None,
+        requests_per_minute: None,
+    };
+
+    const fn new(requests_per_day: i64, tokens_per_day: i64, requests_per_minute: i64) -> Self {
+        Quota {
+            requests_per_day: Some(requests_per_day),
+            tokens_per_day: Some(tokens_per_day),
+            requests_per_minute: Some(requests_per_minute),
+        }
+    }
+
+    // 格式为「每日请求数,每日 token 数,每分钟请求数」，某项填 0 或 unlimited 表示不限
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("unlimited") {
+            return Ok(Quota::UNLIMITED);
+        }
+
+        let limits = value
+            .split(',')
+            .map(|part| {
+                let part = part.trim();
+                if part.eq_ignore_ascii_case("unlimited") {
+                    return Ok(None);
+                }
+                let limit: i64 = part.parse()?;
+                Ok((limit > 0).then_some(limit))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let [requests_per_day, tokens_per_day, requests_per_minute] = limits[..] else {
+            anyhow::bail!("expected `requests_per_day,tokens_per_day,requests_per_minute`, got `{}`", value);
+        };
+
+        Ok(Quota { requests_per_day, tokens_per_day, requests_per_minute })
+    }
+
+    fn is_unlimited(&self) -> bool {
+        *self == Quota::UNLIMITED
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UsagePolicy {
+    pub anonymous: Quota,
+    pub learner: Quota,
+    pub teacher: Quota,
+    pub moderator: Quota,
+    pub admin: Quota,
+    // 部署在反向代理之后时，匿名用户按 X-Forwarded-For 中的第一个地址计量
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for UsagePolicy {
+    fn default() -> Self {
+        Self {
+            anonymous: Quota::new(20, 20_000, 5),
+            learner: Quota::new(200, 200_000, 20),
+            teacher: Quota::new(500, 500_000, 30),
+            moderator: Quota::new(500, 500_000, 30),
+            admin: Quota::UNLIMITED,
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+impl UsagePolicy {
+    pub fn quota_for(&self, role: Option<UserRole>) -> Quota {
+        match role {
+            None => self.anonymous,
+            Some(UserRole::Learner) => self.learner,
+            Some(UserRole::Teacher) => self.teacher,
+            Some(UserRole::Moderator) => self.moderator,
+            Some(UserRole::Admin) => self.admin,
+        }
+    }
+}
+
+// 单次请求的 token 计数，所有克隆都释放后（流式回复结束或客户端断开）写回数据库
+#[derive(Clone)]
+pub struct UsageMeter {
+    inner: Arc<MeterInner>,
+}
+
+struct MeterInner {
+    db: Database,
+    usage_id: Uuid,
+    prompt_tokens: AtomicI64,
+    completion_tokens: AtomicI64,
+    prompt: Mutex<Option<PromptVersion>>,
+}
+
+impl UsageMeter {
+    fn new(db: Database, usage_id: Uuid) -> Self {
+        Self {
+            inner: Arc::new(MeterInner {
+                db,
+                usage_id,
+                prompt_tokens: AtomicI64::new(0),
+                completion_tokens: AtomicI64::new(0),
+                prompt: Mutex::new(None),
+            }),
+        }
+    }
+
+    fn add_prompt(&self, tokens: usize) {
+        self.inner.prompt_tokens.fetch_add(tokens as i64, Ordering::Relaxed);
+    }
+
+    fn add_completion(&self, tokens: usize) {
+        self.inner.completion_tokens.fetch_add(tokens as i64, Ordering::Relaxed);
+    }
+
+    fn current() -> Option<UsageMeter> {
+        CURRENT_METER.try_with(|meter| meter.clone()).ok()
+    }
+}
+
+impl Drop for MeterInner {
+    fn drop(&mut self) {
+        let prompt_tokens = *self.prompt_tokens.get_mut();
+        let completion_tokens = *self.completion_tokens.get_mut();
+        let prompt = self.prompt.get_mut().unwrap().take();
+        if prompt_tokens == 0 && completion_tokens == 0 && prompt.is_none() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let db = self.db.clone();
+        let usage_id = self.usage_id;
+        PENDING_WRITES.fetch_add(1, Ordering::SeqCst);
+        runtime.spawn(async move {
+            if let Err(err) = db
+                .finish_ai_usage(usage_id, prompt_tokens, completion_tokens, prompt.as_ref())
+                .await
+            {
+                tracing::warn!("failed to record AI usage {}: {:?}", usage_id, err);
+            }
+            PENDING_WRITES.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+// 尚未写回数据库的用量记录数
+static PENDING_WRITES: AtomicUsize = AtomicUsize::new(0);
+
+// 停止服务时等待用量记录写完再关闭连接池，否则最后一批请求（如刚结束的流式回答）的用量会丢失
+pub async fn flush() {
+    while PENDING_WRITES.load(Ordering::SeqCst) > 0 {
+        tokio::time::sleep(StdDuration::from_millis(10)).await;
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_METER: UsageMeter;
+}
+
+// 记录本次请求使用的提示词版本，用于按版本对比用量
+pub fn record_prompt(version: &PromptVersion) {
+    if let Some(meter) = UsageMeter::current() {
+        *meter.inner.prompt.lock().unwrap() = Some(version.clone());
+    }
+}
+
+fn message_tokens(messages: &[ChatMessage]) -> usize {
+    messages.iter().map(|message| estimate_tokens(&message.content)).sum()
+}
+
+// 包装实际的 AI 服务，把每次调用的 token 估算计入当前请求的计量器；后台任务（如内容索引）不计量
+pub struct MeteredProvider {
+    inner: SharedAiProvider,
+}
+
+impl MeteredProvider {
+    pub fn new(inner: SharedAiProvider) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl AiProvider for MeteredProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn embedding_model(&self) -> &str {
+        self.inner.embedding_model()
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> anyhow::Result<String> {
+        let meter = UsageMeter::current();
+        if let Some(meter) = &meter {
+            meter.add_prompt(message_tokens(messages));
+        }
+
+        let reply = self.inner.chat(messages).await?;
+        if let Some(meter) = &meter {
+            meter.add_completion(estimate_tokens(&reply));
+        }
+
+        Ok(reply)
+    }
+
+    async fn chat_stream(&self, messages: &[ChatMessage]) -> anyhow::Result<DeltaStream> {
+        let Some(meter) = UsageMeter::current() else {
+            return self.inner.chat_stream(messages).await;
+        };
+        meter.add_prompt(message_tokens(messages));
+
+        let stream = self.inner.chat_stream(messages).await?;
+        Ok(Box::pin(stream.inspect_ok(move |delta| meter.add_completion(estimate_tokens(delta)))))
+    }
+
+    async fn chat_json(&self, messages: &[ChatMessage], name: &str, schema: &Value) -> anyhow::Result<Value> {
+        let meter = UsageMeter::current();
+        if let Some(meter) = &meter {
+            meter.add_prompt(message_tokens(messages));
+        }
+
+        let reply = self.inner.chat_json(messages, name, schema).await?;
+        if let Some(meter) = &meter {
+            meter.add_completion(estimate_tokens(&reply.to_string()));
+        }
+
+        Ok(reply)
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if let Some(meter) = UsageMeter::current() {
+            meter.add_prompt(inputs.iter().map(|input| estimate_tokens(input)).sum());
+        }
+
+        self.inner.embed(inputs).await
+    }
+}
+
+fn client_ip(request: &Request, trust_forwarded_for: bool) -> String {
+    if trust_forwarded_for
+        && let Some(forwarded) = request
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+    {
+        return forwarded.to_string();
+    }
+
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn seconds_until(at: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+    (at - now).num_seconds().max(1) as u64
+}
+
+fn check_quota(quota: &Quota, window: &UsageWindow, now: DateTime<Utc>, tomorrow: DateTime<Utc>) -> Result<(), AppError> {
+    let over_day = quota.requests_per_day.is_some_and(|limit| window.day_requests >= limit)
+        || quota.tokens_per_day.is_some_and(|limit| window.day_tokens >= limit);
+    if over_day {
+        return Err(AppError::RateLimited {
+            message: "今日 AI 使用额度已用完，请明天再试".to_string(),
+            retry_after: seconds_until(tomorrow, now),
+        });
+    }
+
+    if quota.requests_per_minute.is_some_and(|limit| window.minute_requests >= limit) {
+        let reset_at = window.minute_oldest.unwrap_or(now) + Duration::minutes(1);
+        return Err(AppError::RateLimited {
+            message: "AI 请求过于频繁，请稍后再试".to_string(),
+            retry_after: seconds_until(reset_at, now),
+        });
+    }
+
+    Ok(())
+}
+
+// AI 接口的计量与限流：登录用户按用户计量，匿名用户按 IP 计量，配额按自然日（UTC）重置
+pub async fn enforce_quota(
+    State(state): State<AppState>,
+    current_user: Option<CurrentUser>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let user_id = current_user.as_ref().map(|user| user.id);
+    let ip = client_ip(&request, state.usage.trust_forwarded_for);
+    let endpoint = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let quota = state.usage.quota_for(current_user.as_ref().map(|user| user.role));
+    let usage_id = if quota.is_unlimited() {
+        state.db.create_ai_usage(user_id, &ip, &endpoint).await?
+    } else {
+        // 检查配额与记录本次调用在同一事务中完成，并发请求不会同时通过检查
+        let now = Utc::now();
+        let today = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        state
+            .db
+            .reserve_ai_usage(user_id, &ip, &endpoint, today, now - Duration::minutes(1), |window| {
+                check_quota(&quota, window, now, today + Duration::days(1))
+            })
+            .await??
+    };
+    let meter = UsageMeter::new(state.db.clone(), usage_id);
+
+    Ok(CURRENT_METER.scope(meter, next.run(request)).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(day_requests: i64, day_tokens: i64, minute_requests: i64) -> UsageWindow {
+        UsageWindow { day_requests, day_tokens, minute_requests, minute_oldest: None }
+    }
+
+    fn retry_after(result: Result<(), AppError>) -> u64 {
+        match result {
+            Err(AppError::RateLimited { retry_after, .. }) => retry_after,
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_limits_and_unlimited_parts() {
+        assert_eq!(Quota::parse("200,200000,20").unwrap(), Quota::new(200, 200_000, 20));
+        assert_eq!(
+            Quota::parse(" 10 , 0 , Unlimited ").unwrap(),
+            Quota { requests_per_day: Some(10), tokens_per_day: None, requests_per_minute: None }
+        );
+        assert!(Quota::parse("unlimited").unwrap().is_unlimited());
+        assert!(Quota::parse("UNLIMITED").unwrap().is_unlimited());
+        assert!(Quota::parse("0,0,0").unwrap().is_unlimited());
+    }
+
+    #[test]
+    fn rejects_malformed_quotas() {
+        for value in ["", "10,20", "1,2,3,4", "ten,20,30", "10,,30", "1.5,2,3"] {
+            assert!(Quota::parse(value).is_err(), "{:?} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn requests_are_rejected_once_the_daily_limit_is_reached() {
+        let quota = Quota::new(20, 1_000, 5);
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let tomorrow = now + Duration::hours(3);
+
+        assert!(check_quota(&quota, &window(19, 999, 4), now, tomorrow).is_ok());
+        assert_eq!(retry_after(check_quota(&quota, &window(20, 0, 0), now, tomorrow)), 3 * 3600);
+        assert_eq!(retry_after(check_quota(&quota, &window(0, 1_000, 0), now, tomorrow)), 3 * 3600);
+    }
+
+    #[test]
+    fn minute_limit_retries_after_the_oldest_request_expires() {
+        let quota = Quota::new(20, 1_000, 5);
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let tomorrow = now + Duration::hours(3);
+
+        let mut at_limit = window(5, 0, 5);
+        at_limit.minute_oldest = Some(now - Duration::seconds(45));
+        assert_eq!(retry_after(check_quota(&quota, &at_limit, now, tomorrow)), 15);
+
+        assert!(check_quota(&Quota::UNLIMITED, &window(1_000, 1_000_000, 1_000), now, tomorrow).is_ok());
+    }
+}
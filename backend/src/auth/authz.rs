@@ -0,0 +1,41 @@
+use uuid::Uuid;
+
+use crate::auth::CurrentUser;
+use crate::error::AppError;
+use crate::models::UserRole;
+
+// 用户资料只有本人或管理员可以修改
+pub const USER_ADMIN_ROLE: UserRole = UserRole::Admin;
+// 内容可以由作者本人或版主及以上角色管理
+pub const CONTENT_ADMIN_ROLE: UserRole = UserRole::Moderator;
+
+impl CurrentUser {
+    pub fn has_role(&self, role: UserRole) -> bool {
+        self.role >= role
+    }
+
+    pub fn can_modify(&self, owner_id: Uuid, privileged_role: UserRole) -> bool {
+        self.id == owner_id || self.has_role(privileged_role)
+    }
+}
+
+pub fn ensure_can_modify(
+    user: &CurrentUser,
+    owner_id: Uuid,
+    privileged_role: UserRole,
+    message: &str,
+) -> Result<(), AppError> {
+    if user.can_modify(owner_id, privileged_role) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(message.to_string()))
+    }
+}
+
+pub fn ensure_role(user: &CurrentUser, role: UserRole) -> Result<(), AppError> {
+    if user.has_role(role) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden("权限不足".to_string()))
+    }
+}
@@ -0,0 +1,70 @@
+use axum::{
+    extract::{FromRef, FromRequestParts, OptionalFromRequestParts},
+    http::request::Parts,
+};
+use jsonwebtoken::errors::ErrorKind;
+use uuid::Uuid;
+
+use crate::auth::{bearer_token, JwtKeys};
+use crate::database::Database;
+use crate::error::{AppError, ErrorCode};
+use crate::models::UserRole;
+
+// 当前登录用户，从 Authorization: Bearer <access_token> 中解析
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    pub id: Uuid,
+    pub role: UserRole,
+}
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    Database: FromRef<S>,
+    JwtKeys: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(&parts.headers)
+            .ok_or_else(|| AppError::Unauthorized("缺少访问令牌".to_string()))?;
+
+        let keys = JwtKeys::from_ref(state);
+        let claims = keys.decode_access_token(token).map_err(|e| match e.kind() {
+            ErrorKind::ExpiredSignature => AppError::Coded(ErrorCode::TokenExpired),
+            _ => AppError::Coded(ErrorCode::TokenInvalid),
+        })?;
+
+        // 令牌签发后用户可能已被删除
+        let db = Database::from_ref(state);
+        let user = db
+            .get_user_by_id(claims.sub)
+            .await?
+            .ok_or(AppError::Coded(ErrorCode::TokenInvalid))?;
+
+        Ok(CurrentUser {
+            id: user.id,
+            role: user.role,
+        })
+    }
+}
+
+// 可选登录：未携带令牌时为 None，携带了无效令牌仍然拒绝
+impl<S> OptionalFromRequestParts<S> for CurrentUser
+where
+    Database: FromRef<S>,
+    JwtKeys: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if bearer_token(&parts.headers).is_none() {
+            return Ok(None);
+        }
+
+        <CurrentUser as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
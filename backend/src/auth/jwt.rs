@@ -0,0 +1,71 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const ACCESS_TOKEN_TYPE: &str = "access";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: Uuid,
+    pub typ: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+#[derive(Clone)]
+pub struct JwtKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    pub access_token_ttl: Duration,
+    pub refresh_token_ttl: Duration,
+}
+
+impl JwtKeys {
+    pub fn new(secret: &[u8], access_token_ttl: Duration, refresh_token_ttl: Duration) -> Self {
+        JwtKeys {
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            access_token_ttl,
+            refresh_token_ttl,
+        }
+    }
+
+    pub fn issue_access_token(&self, user_id: Uuid) -> Result<String> {
+        let now = Utc::now();
+        let claims = Claims {
+            sub: user_id,
+            typ: ACCESS_TOKEN_TYPE.to_string(),
+            iat: now.timestamp(),
+            exp: (now + self.access_token_ttl).timestamp(),
+        };
+
+        Ok(encode(&Header::new(Algorithm::HS256), &claims, &self.encoding)?)
+    }
+
+    pub fn decode_access_token(&self, token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.leeway = 0;
+
+        let claims = decode::<Claims>(token, &self.decoding, &validation)?.claims;
+        if claims.typ != ACCESS_TOKEN_TYPE {
+            return Err(jsonwebtoken::errors::ErrorKind::InvalidToken.into());
+        }
+
+        Ok(claims)
+    }
+}
+
+// 刷新令牌是不透明的随机串，数据库中只保存其 SHA-256 摘要
+pub fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub fn hash_refresh_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
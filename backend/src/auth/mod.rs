@@ -0,0 +1,19 @@
+pub mod authz;
+pub mod extractor;
+pub mod jwt;
+pub mod password;
+
+use axum::http::{header::AUTHORIZATION, HeaderMap};
+
+pub use extractor::CurrentUser;
+pub use jwt::JwtKeys;
+
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
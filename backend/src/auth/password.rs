@@ -0,0 +1,124 @@
+use std::sync::LazyLock;
+
+use anyhow::{anyhow, Result};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+
+// Argon2id 参数（OWASP 推荐的最低配置：19 MiB 内存，2 次迭代，1 并行度）
+// 参数会随哈希一起以 PHC 字符串形式存储，调整后旧哈希会在登录时自动重新计算
+const MEMORY_COST_KIB: u32 = 19 * 1024;
+const TIME_COST: u32 = 2;
+const PARALLELISM: u32 = 1;
+
+// 用户不存在时用于校验的占位哈希，参数与真实哈希相同，保证两种情况耗时一致
+static DUMMY_HASH: LazyLock<String> =
+    LazyLock::new(|| hash_password("dummy-password").expect("failed to hash dummy password"));
+
+fn hasher() -> Argon2<'static> {
+    let params = Params::new(MEMORY_COST_KIB, TIME_COST, PARALLELISM, None)
+        .expect("invalid Argon2 parameters");
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+}
+
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = hasher()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| anyhow!("failed to hash password: {}", e))?;
+
+    Ok(hash.to_string())
+}
+
+// 无法解析的哈希（例如历史数据中的占位值）一律视为校验失败
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(parsed) => hasher().verify_password(password.as_bytes(), &parsed).is_ok(),
+        Err(_) => false,
+    }
+}
+
+pub fn needs_rehash(password_hash: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(password_hash) else {
+        return true;
+    };
+
+    if parsed.algorithm != Algorithm::Argon2id.ident()
+        || parsed.version != Some(Version::V0x13.into())
+    {
+        return true;
+    }
+
+    match Params::try_from(&parsed) {
+        Ok(params) => {
+            params.m_cost() != MEMORY_COST_KIB
+                || params.t_cost() != TIME_COST
+                || params.p_cost() != PARALLELISM
+        }
+        Err(_) => true,
+    }
+}
+
+// Argon2 是刻意设计的 CPU/内存密集型计算，放到阻塞线程池中执行以免占用异步运行时
+pub async fn hash_password_blocking(password: String) -> Result<String> {
+    tokio::task::spawn_blocking(move || hash_password(&password)).await?
+}
+
+pub async fn verify_dummy_blocking(password: String) -> Result<()> {
+    tokio::task::spawn_blocking(move || verify_password(&password, &DUMMY_HASH)).await?;
+    Ok(())
+}
+
+pub async fn verify_password_blocking(password: String, password_hash: String) -> Result<bool> {
+    Ok(tokio::task::spawn_blocking(move || verify_password(&password, &password_hash)).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_with(algorithm: Algorithm, memory_kib: u32, time: u32) -> String {
+        let params = Params::new(memory_kib, time, PARALLELISM, None).unwrap();
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::new(algorithm, Version::V0x13, params)
+            .hash_password(b"correct horse", &salt)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn hash_and_verify_round_trip() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$"));
+        assert!(verify_password("correct horse", &hash));
+        // 每次使用新的盐
+        assert_ne!(hash, hash_password("correct horse").unwrap());
+    }
+
+    #[test]
+    fn wrong_password_is_rejected() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(!verify_password("battery staple", &hash));
+        assert!(!verify_password("", &hash));
+        assert!(!verify_password("correct horse", "placeholder_hash"));
+    }
+
+    #[test]
+    fn current_hashes_do_not_need_rehash() {
+        assert!(!needs_rehash(&hash_password("correct horse").unwrap()));
+    }
+
+    #[test]
+    fn legacy_and_weaker_hashes_need_rehash() {
+        assert!(needs_rehash("placeholder_hash"));
+        assert!(needs_rehash("$2b$12$C6UzMDM.H6dfI/f/IKcEeO5Q7FhEwHTCMk7fYwC2O1MV4VYtmfyPW"));
+        assert!(needs_rehash(&hash_with(Algorithm::Argon2i, MEMORY_COST_KIB, TIME_COST)));
+        assert!(needs_rehash(&hash_with(Algorithm::Argon2id, 4 * 1024, TIME_COST)));
+        assert!(needs_rehash(&hash_with(Algorithm::Argon2id, MEMORY_COST_KIB, 1)));
+
+        // 旧参数的哈希仍然可以通过校验，登录成功后再升级
+        let weaker = hash_with(Algorithm::Argon2id, 4 * 1024, 1);
+        assert!(verify_password("correct horse", &weaker));
+    }
+}
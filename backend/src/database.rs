@@ -1,6 +1,9 @@
-use sqlx::{PgPool, FromRow};
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgPoolOptions, PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 use anyhow::Result;
+use crate::auth::password;
+use crate::config::DatabaseConfig;
 use crate::models::*;
 
 #[derive(Clone)]
@@ -9,27 +12,40 @@ pub struct Database {
 }
 
 impl Database {
-    pub async fn new(database_url: &str) -> Result<Self> {
-        let pool = PgPool::connect(database_url).await?;
-        
-        // Run migrations - for development, we'll handle this manually
-        // sqlx::migrate!("./migrations").run(&pool).await?;
-        
+    pub async fn new(config: &DatabaseConfig) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .acquire_timeout(config.acquire_timeout)
+            .idle_timeout(config.idle_timeout)
+            .connect(config.url.expose())
+            .await?;
         Ok(Database { pool })
     }
 
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
     // User operations
     pub async fn create_user(&self, request: &CreateUserRequest) -> Result<User> {
+        let password_hash = password::hash_password_blocking(request.password.clone()).await?;
+
         let user = sqlx::query_as::<_, User>(
             r#"
             INSERT INTO users (username, email, password_hash, display_name, bio, avatar_url)
             VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, username, email, password_hash, display_name, bio, avatar_url, created_at, updated_at
+            RETURNING id, username, email, password_hash, display_name, bio, avatar_url, role, created_at, updated_at
             "#
         )
         .bind(&request.username)
         .bind(&request.email)
-        .bind("placeholder_hash") // In real app, hash the password
+        .bind(&password_hash)
         .bind(&request.display_name)
         .bind(&request.bio)
         .bind(&request.avatar_url)
@@ -61,12 +77,64 @@ impl Database {
         Ok(user)
     }
 
-    pub async fn get_all_users(&self) -> Result<Vec<User>> {
-        let users = sqlx::query_as::<_, User>(
-            "SELECT * FROM users ORDER BY created_at DESC"
+    // Returns the user only when the password matches. Hashes created with outdated
+    // Argon2 parameters are transparently upgraded on a successful login.
+    pub async fn verify_credentials(&self, username: &str, plain_password: &str) -> Result<Option<User>> {
+        let Some(mut user) = self.get_user_by_username(username).await? else {
+            // 用户不存在时同样执行一次 Argon2 校验，避免通过响应时间判断用户名是否存在
+            password::verify_dummy_blocking(plain_password.to_string()).await?;
+            return Ok(None);
+        };
+
+        let valid = password::verify_password_blocking(
+            plain_password.to_string(),
+            user.password_hash.clone(),
         )
-        .fetch_all(&self.pool)
         .await?;
+        if !valid {
+            return Ok(None);
+        }
+
+        if password::needs_rehash(&user.password_hash) {
+            let new_hash = password::hash_password_blocking(plain_password.to_string()).await?;
+            self.update_password_hash(user.id, &new_hash).await?;
+            user.password_hash = new_hash;
+        }
+
+        Ok(Some(user))
+    }
+
+    pub async fn update_password_hash(&self, id: Uuid, password_hash: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE users SET password_hash = $2 WHERE id = $1"
+        )
+        .bind(id)
+        .bind(password_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Keyset pagination on (created_at, id); fetches `limit` rows after the cursor.
+    pub async fn list_users(&self, after: Option<&Cursor>, limit: i64) -> Result<Vec<User>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM users");
+        if let Some(cursor) = after {
+            query
+                .push(" WHERE (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit);
+
+        let users = query
+            .build_query_as::<User>()
+            .fetch_all(&self.pool)
+            .await?;
 
         Ok(users)
     }
@@ -79,7 +147,7 @@ impl Database {
                 bio = COALESCE($3, bio),
                 avatar_url = COALESCE($4, avatar_url)
             WHERE id = $1
-            RETURNING id, username, email, password_hash, display_name, bio, avatar_url, created_at, updated_at
+            RETURNING id, username, email, password_hash, display_name, bio, avatar_url, role, created_at, updated_at
             "#
         )
         .bind(id)
@@ -92,6 +160,23 @@ impl Database {
         Ok(user)
     }
 
+    pub async fn update_user_role(&self, id: Uuid, role: UserRole) -> Result<Option<User>> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET role = $2
+            WHERE id = $1
+            RETURNING id, username, email, password_hash, display_name, bio, avatar_url, role, created_at, updated_at
+            "#
+        )
+        .bind(id)
+        .bind(role)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
     pub async fn delete_user(&self, id: Uuid) -> Result<bool> {
         let result = sqlx::query(
             "DELETE FROM users WHERE id = $1"
@@ -103,6 +188,97 @@ impl Database {
         Ok(result.rows_affected() > 0)
     }
 
+    // Refresh token operations
+    pub async fn create_refresh_token(&self, user_id: Uuid, token_hash: &str, expires_at: DateTime<Utc>) -> Result<RefreshToken> {
+        let token = sqlx::query_as::<_, RefreshToken>(
+            r#"
+            INSERT INTO refresh_tokens (user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            RETURNING id, user_id, token_hash, expires_at, revoked_at, replaced_by, created_at
+            "#
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    // Atomically swaps an active refresh token for a new one. Presenting a token that
+    // was already rotated or revoked is treated as theft and revokes the whole family.
+    pub async fn rotate_refresh_token(&self, old_hash: &str, new_hash: &str, expires_at: DateTime<Utc>) -> Result<RefreshOutcome> {
+        let mut tx = self.pool.begin().await?;
+
+        let current = sqlx::query_as::<_, RefreshToken>(
+            "SELECT * FROM refresh_tokens WHERE token_hash = $1 FOR UPDATE"
+        )
+        .bind(old_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(current) = current else {
+            return Ok(RefreshOutcome::Invalid);
+        };
+
+        // 已通过退出登录吊销的令牌（没有后继令牌）只是失效，不影响该用户的其他会话
+        if current.revoked_at.is_some() && current.replaced_by.is_none() {
+            return Ok(RefreshOutcome::Invalid);
+        }
+
+        if current.revoked_at.is_some() {
+            sqlx::query(
+                "UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL"
+            )
+            .bind(current.user_id)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Ok(RefreshOutcome::Reused);
+        }
+
+        if current.expires_at <= Utc::now() {
+            return Ok(RefreshOutcome::Expired);
+        }
+
+        let replacement_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO refresh_tokens (user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#
+        )
+        .bind(current.user_id)
+        .bind(new_hash)
+        .bind(expires_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW(), replaced_by = $2 WHERE id = $1"
+        )
+        .bind(current.id)
+        .bind(replacement_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(RefreshOutcome::Rotated { user_id: current.user_id })
+    }
+
+    pub async fn revoke_refresh_token(&self, token_hash: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW() WHERE token_hash = $1 AND revoked_at IS NULL"
+        )
+        .bind(token_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     // Content operations
     pub async fn create_content(&self, request: &CreateContentRequest, author_id: Uuid) -> Result<Content> {
         let content = sqlx::query_as::<_, Content>(
@@ -134,12 +310,53 @@ impl Database {
         Ok(content)
     }
 
-    pub async fn get_all_contents(&self) -> Result<Vec<Content>> {
-        let contents = sqlx::query_as::<_, Content>(
-            "SELECT * FROM contents ORDER BY created_at DESC"
-        )
-        .fetch_all(&self.pool)
-        .await?;
+    // Keyset pagination for contents. The cursor columns follow the sort order:
+    // (created_at, id) for newest, (likes_count, created_at, id) for most liked.
+    pub async fn list_contents(&self, filter: &ContentListQuery, after: Option<&Cursor>, limit: i64) -> Result<Vec<Content>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM contents WHERE TRUE");
+
+        if let Some(content_type) = &filter.content_type {
+            query.push(" AND content_type = ").push_bind(content_type);
+        }
+        if let Some(tag) = &filter.tag {
+            query.push(" AND ").push_bind(tag).push(" = ANY(tags)");
+        }
+        if let Some(author_id) = filter.author_id {
+            query.push(" AND author_id = ").push_bind(author_id);
+        }
+
+        match (filter.sort, after) {
+            (ContentSort::Newest, Some(cursor)) => {
+                query
+                    .push(" AND (created_at, id) < (")
+                    .push_bind(cursor.created_at)
+                    .push(", ")
+                    .push_bind(cursor.id)
+                    .push(")");
+            }
+            (ContentSort::MostLiked, Some(cursor)) => {
+                query
+                    .push(" AND (likes_count, created_at, id) < (")
+                    .push_bind(cursor.likes_count.unwrap_or(i32::MAX))
+                    .push(", ")
+                    .push_bind(cursor.created_at)
+                    .push(", ")
+                    .push_bind(cursor.id)
+                    .push(")");
+            }
+            (_, None) => {}
+        }
+
+        query.push(match filter.sort {
+            ContentSort::Newest => " ORDER BY created_at DESC, id DESC",
+            ContentSort::MostLiked => " ORDER BY likes_count DESC, created_at DESC, id DESC",
+        });
+        query.push(" LIMIT ").push_bind(limit);
+
+        let contents = query
+            .build_query_as::<Content>()
+            .fetch_all(&self.pool)
+            .await?;
 
         Ok(contents)
     }
@@ -204,64 +421,1131 @@ impl Database {
     }
 
     // Comment operations
-    pub async fn create_comment(&self, request: &CreateCommentRequest, author_id: Uuid) -> Result<Comment> {
+    pub async fn create_comment(&self, content_id: Uuid, request: &CreateCommentRequest, author_id: Uuid, parent: Option<&Comment>) -> Result<Comment> {
         let comment = sqlx::query_as::<_, Comment>(
             r#"
-            INSERT INTO comments (content_id, author_id, content)
-            VALUES ($1, $2, $3)
-            RETURNING id, content_id, author_id, content, created_at, updated_at
+            INSERT INTO comments (content_id, author_id, content, parent_id, depth)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, content_id, author_id, parent_id, depth, reply_count, content, created_at, updated_at
             "#
         )
-        .bind(request.content_id)
+        .bind(content_id)
         .bind(author_id)
         .bind(&request.content)
+        .bind(parent.map(|p| p.id))
+        .bind(parent.map_or(0, |p| p.depth + 1))
         .fetch_one(&self.pool)
         .await?;
 
         Ok(comment)
     }
 
-    pub async fn get_comments_by_content_id(&self, content_id: Uuid) -> Result<Vec<Comment>> {
+    // Comments are listed oldest first, so the keyset moves forward on (created_at, id).
+    pub async fn get_comments_by_content_id(&self, content_id: Uuid, roots_only: bool, after: Option<&Cursor>, limit: i64) -> Result<Vec<Comment>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM comments WHERE content_id = ");
+        query.push_bind(content_id);
+        if roots_only {
+            query.push(" AND parent_id IS NULL");
+        }
+        if let Some(cursor) = after {
+            query
+                .push(" AND (created_at, id) > (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at ASC, id ASC LIMIT ")
+            .push_bind(limit);
+
+        let comments = query
+            .build_query_as::<Comment>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(comments)
+    }
+
+    pub async fn get_replies(&self, parent_id: Uuid, after: Option<&Cursor>, limit: i64) -> Result<Vec<Comment>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM comments WHERE parent_id = ");
+        query.push_bind(parent_id);
+        if let Some(cursor) = after {
+            query
+                .push(" AND (created_at, id) > (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at ASC, id ASC LIMIT ")
+            .push_bind(limit);
+
+        let comments = query
+            .build_query_as::<Comment>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(comments)
+    }
+
+    // All descendants of the given comments, oldest first. Depth is bounded by
+    // MAX_COMMENT_DEPTH so the recursion stays shallow.
+    // Returns at most `per_root` of the earliest replies under each root. Replies are
+    // always newer than their parent, so the earliest ones form a connected tree.
+    pub async fn get_comment_descendants(&self, root_ids: &[Uuid], per_root: i64) -> Result<Vec<Comment>> {
         let comments = sqlx::query_as::<_, Comment>(
-            "SELECT * FROM comments WHERE content_id = $1 ORDER BY created_at ASC"
+            r#"
+            WITH RECURSIVE thread AS (
+                SELECT c.*, c.parent_id AS root_id FROM comments c WHERE c.parent_id = ANY($1)
+                UNION ALL
+                SELECT c.*, t.root_id FROM comments c
+                JOIN thread t ON c.parent_id = t.id
+                WHERE c.depth <= $3
+            ),
+            ranked AS (
+                SELECT thread.*, ROW_NUMBER() OVER (PARTITION BY root_id ORDER BY created_at, id) AS position
+                FROM thread
+            )
+            SELECT id, content_id, author_id, parent_id, depth, reply_count, content, created_at, updated_at
+            FROM ranked
+            WHERE position <= $2
+            ORDER BY created_at ASC, id ASC
+            "#
         )
-        .bind(content_id)
+        .bind(root_ids)
+        .bind(per_root)
+        .bind(MAX_COMMENT_DEPTH)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(comments)
     }
 
+    pub async fn get_comment_by_id(&self, id: Uuid) -> Result<Option<Comment>> {
+        let comment = sqlx::query_as::<_, Comment>(
+            "SELECT * FROM comments WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    pub async fn update_comment(&self, id: Uuid, request: &UpdateCommentRequest) -> Result<Option<Comment>> {
+        let comment = sqlx::query_as::<_, Comment>(
+            r#"
+            UPDATE comments
+            SET content = $2
+            WHERE id = $1
+            RETURNING id, content_id, author_id, parent_id, depth, reply_count, content, created_at, updated_at
+            "#
+        )
+        .bind(id)
+        .bind(&request.content)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    pub async fn delete_comment(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM comments WHERE id = $1"
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     // Friendship operations
-    pub async fn create_friendship(&self, user1_id: Uuid, user2_id: Uuid) -> Result<Friendship> {
+    pub async fn create_friendship(&self, requester_id: Uuid, addressee_id: Uuid, message: Option<&str>) -> Result<Friendship> {
         let friendship = sqlx::query_as::<_, Friendship>(
             r#"
-            INSERT INTO friendships (user1_id, user2_id, status)
-            VALUES ($1, $2, 'pending')
-            RETURNING id, user1_id, user2_id, status, created_at, updated_at
+            INSERT INTO friendships (user1_id, user2_id, status, requested_by, request_message)
+            VALUES ($1, $2, 'pending', $1, $3)
+            RETURNING *
             "#
         )
-        .bind(user1_id)
-        .bind(user2_id)
+        .bind(requester_id)
+        .bind(addressee_id)
+        .bind(message)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(friendship)
     }
 
-    pub async fn get_friendships_by_user_id(&self, user_id: Uuid) -> Result<Vec<Friendship>> {
-        let friendships = sqlx::query_as::<_, Friendship>(
+    pub async fn get_friendship_by_id(&self, id: Uuid) -> Result<Option<Friendship>> {
+        let friendship = sqlx::query_as::<_, Friendship>(
+            "SELECT * FROM friendships WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(friendship)
+    }
+
+    // Looks up the single relationship row between two users in either direction.
+    pub async fn get_friendship_between(&self, user_a: Uuid, user_b: Uuid) -> Result<Option<Friendship>> {
+        let friendship = sqlx::query_as::<_, Friendship>(
             r#"
-            SELECT id, user1_id, user2_id, status, created_at, updated_at
-            FROM friendships 
-            WHERE user1_id = $1 OR user2_id = $1
-            ORDER BY created_at DESC
+            SELECT * FROM friendships
+            WHERE LEAST(user1_id, user2_id) = LEAST($1::uuid, $2::uuid)
+              AND GREATEST(user1_id, user2_id) = GREATEST($1::uuid, $2::uuid)
+            "#
+        )
+        .bind(user_a)
+        .bind(user_b)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(friendship)
+    }
+
+    // Turns a previously declined relationship back into a pending request from `requester_id`.
+    pub async fn reopen_friendship(&self, id: Uuid, requester_id: Uuid, message: Option<&str>) -> Result<Option<Friendship>> {
+        let friendship = sqlx::query_as::<_, Friendship>(
+            r#"
+            UPDATE friendships
+            SET status = 'pending', requested_by = $2, request_message = $3, responded_at = NULL
+            WHERE id = $1 AND status = 'declined'
+            RETURNING *
+            "#
+        )
+        .bind(id)
+        .bind(requester_id)
+        .bind(message)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(friendship)
+    }
+
+    // Answers a pending request; returns None if it was no longer pending.
+    pub async fn respond_to_friendship(&self, id: Uuid, status: FriendshipStatus) -> Result<Option<Friendship>> {
+        let friendship = sqlx::query_as::<_, Friendship>(
+            r#"
+            UPDATE friendships
+            SET status = $2, responded_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING *
+            "#
+        )
+        .bind(id)
+        .bind(status)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(friendship)
+    }
+
+    pub async fn block_user(&self, blocker_id: Uuid, target_id: Uuid) -> Result<Friendship> {
+        let friendship = match self.get_friendship_between(blocker_id, target_id).await? {
+            Some(existing) => {
+                sqlx::query_as::<_, Friendship>(
+                    r#"
+                    UPDATE friendships
+                    SET status = 'blocked', blocked_by = $2, responded_at = NOW()
+                    WHERE id = $1
+                    RETURNING *
+                    "#
+                )
+                .bind(existing.id)
+                .bind(blocker_id)
+                .fetch_one(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, Friendship>(
+                    r#"
+                    INSERT INTO friendships (user1_id, user2_id, status, requested_by, blocked_by, responded_at)
+                    VALUES ($1, $2, 'blocked', $1, $1, NOW())
+                    RETURNING *
+                    "#
+                )
+                .bind(blocker_id)
+                .bind(target_id)
+                .fetch_one(&self.pool)
+                .await?
+            }
+        };
+
+        Ok(friendship)
+    }
+
+    pub async fn delete_friendship(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM friendships WHERE id = $1"
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Blocked rows are only visible to the user who created the block.
+    pub async fn get_friendships_by_user_id(&self, user_id: Uuid, status: FriendshipStatus, after: Option<&Cursor>, limit: i64) -> Result<Vec<Friendship>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM friendships WHERE (user1_id = ");
+        query
+            .push_bind(user_id)
+            .push(" OR user2_id = ")
+            .push_bind(user_id)
+            .push(") AND status = ")
+            .push_bind(status);
+        if status == FriendshipStatus::Blocked {
+            query.push(" AND blocked_by = ").push_bind(user_id);
+        }
+        if let Some(cursor) = after {
+            query
+                .push(" AND (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit);
+
+        let friendships = query
+            .build_query_as::<Friendship>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(friendships)
+    }
+
+    pub async fn create_ai_conversation(&self, user_id: Uuid, title: Option<&str>) -> Result<AiConversation> {
+        let conversation = sqlx::query_as::<_, AiConversation>(
+            r#"
+            INSERT INTO ai_conversations (user_id, title)
+            VALUES ($1, $2)
+            RETURNING *
             "#
         )
         .bind(user_id)
+        .bind(title)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(conversation)
+    }
+
+    pub async fn get_ai_conversation_by_id(&self, id: Uuid) -> Result<Option<AiConversation>> {
+        let conversation = sqlx::query_as::<_, AiConversation>(
+            "SELECT * FROM ai_conversations WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(conversation)
+    }
+
+    pub async fn list_ai_conversations(&self, user_id: Uuid, after: Option<&Cursor>, limit: i64) -> Result<Vec<AiConversation>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM ai_conversations WHERE user_id = ");
+        query.push_bind(user_id);
+        if let Some(cursor) = after {
+            query
+                .push(" AND (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit);
+
+        let conversations = query
+            .build_query_as::<AiConversation>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(conversations)
+    }
+
+    pub async fn delete_ai_conversation(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM ai_conversations WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_ai_messages(&self, conversation_id: Uuid) -> Result<Vec<AiMessage>> {
+        let messages = sqlx::query_as::<_, AiMessage>(
+            "SELECT * FROM ai_messages WHERE conversation_id = $1 ORDER BY created_at, id"
+        )
+        .bind(conversation_id)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(friendships)
+        Ok(messages)
+    }
+
+    // Stores a question/answer pair. clock_timestamp() keeps the two rows ordered
+    // within the transaction; the first question becomes the title if none was set.
+    pub async fn append_ai_turn(&self, conversation_id: Uuid, question: &str, answer: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for (role, content) in [("user", question), ("assistant", answer)] {
+            sqlx::query(
+                r#"
+                INSERT INTO ai_messages (conversation_id, role, content, created_at)
+                VALUES ($1, $2, $3, clock_timestamp())
+                "#
+            )
+            .bind(conversation_id)
+            .bind(role)
+            .bind(content)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let title: String = question.trim().chars().take(50).collect();
+        sqlx::query(
+            r#"
+            UPDATE ai_conversations
+            SET updated_at = NOW(), title = COALESCE(title, $2)
+            WHERE id = $1
+            "#
+        )
+        .bind(conversation_id)
+        .bind(title)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Replaces all chunks of a content item in one transaction so readers never see a mix.
+    pub async fn replace_content_chunks(&self, content_id: Uuid, model: &str, chunks: &[(String, Vec<f32>)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM content_chunks WHERE content_id = $1")
+            .bind(content_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (index, (body, embedding)) in chunks.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO content_chunks (content_id, chunk_index, body, embedding, embedding_model)
+                VALUES ($1, $2, $3, $4, $5)
+                "#
+            )
+            .bind(content_id)
+            .bind(index as i32)
+            .bind(body)
+            .bind(embedding)
+            .bind(model)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_content_chunks(&self, model: &str) -> Result<Vec<ContentChunk>> {
+        let chunks = sqlx::query_as::<_, ContentChunk>(
+            r#"
+            SELECT k.content_id, c.title, k.body, k.embedding
+            FROM content_chunks k
+            JOIN contents c ON c.id = k.content_id
+            WHERE k.embedding_model = $1
+            ORDER BY k.content_id, k.chunk_index
+            "#
+        )
+        .bind(model)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(chunks)
+    }
+
+    // Contents that have no chunks embedded with the given model yet. Blank bodies
+    // produce no chunks, so they are skipped instead of being re-indexed on every start.
+    pub async fn get_unindexed_contents(&self, model: &str) -> Result<Vec<Content>> {
+        let contents = sqlx::query_as::<_, Content>(
+            r#"
+            SELECT * FROM contents c
+            WHERE c.body ~ '\S'
+            AND NOT EXISTS (
+                SELECT 1 FROM content_chunks k
+                WHERE k.content_id = c.id AND k.embedding_model = $1
+            )
+            ORDER BY c.created_at
+            "#
+        )
+        .bind(model)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(contents)
+    }
+
+    pub async fn create_study_plan(&self, user_id: Uuid, request: &SaveStudyPlanRequest) -> Result<StudyPlan> {
+        let study_plan = sqlx::query_as::<_, StudyPlan>(
+            r#"
+            INSERT INTO study_plans (user_id, subject, level, goals, plan)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#
+        )
+        .bind(user_id)
+        .bind(&request.subject)
+        .bind(&request.level)
+        .bind(&request.goals)
+        .bind(sqlx::types::Json(&request.plan))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(study_plan)
+    }
+
+    pub async fn get_study_plan_by_id(&self, id: Uuid) -> Result<Option<StudyPlan>> {
+        let study_plan = sqlx::query_as::<_, StudyPlan>("SELECT * FROM study_plans WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(study_plan)
+    }
+
+    pub async fn list_study_plans(&self, user_id: Uuid, after: Option<&Cursor>, limit: i64) -> Result<Vec<StudyPlan>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM study_plans WHERE user_id = ");
+        query.push_bind(user_id);
+        if let Some(cursor) = after {
+            query
+                .push(" AND (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit);
+
+        let study_plans = query
+            .build_query_as::<StudyPlan>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(study_plans)
+    }
+
+    pub async fn update_study_plan(&self, id: Uuid, plan: &PlanDocument) -> Result<Option<StudyPlan>> {
+        let study_plan = sqlx::query_as::<_, StudyPlan>(
+            r#"
+            UPDATE study_plans
+            SET plan = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#
+        )
+        .bind(id)
+        .bind(sqlx::types::Json(plan))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(study_plan)
+    }
+
+    // Locks the plan row so concurrent task updates are not lost.
+    // Returns None when the plan or the task does not exist.
+    pub async fn set_study_plan_task_completed(&self, id: Uuid, task_id: Uuid, completed: bool) -> Result<Option<StudyPlan>> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(mut study_plan) = sqlx::query_as::<_, StudyPlan>(
+            "SELECT * FROM study_plans WHERE id = $1 FOR UPDATE"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let Some(task) = study_plan.plan.tasks_mut().find(|task| task.id == task_id) else {
+            return Ok(None);
+        };
+        if task.completed != completed {
+            task.completed = completed;
+            task.completed_at = completed.then(Utc::now);
+        }
+
+        let study_plan = sqlx::query_as::<_, StudyPlan>(
+            r#"
+            UPDATE study_plans
+            SET plan = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#
+        )
+        .bind(id)
+        .bind(&study_plan.plan)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(study_plan))
+    }
+
+    pub async fn delete_study_plan(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM study_plans WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_existing_content_ids(&self, ids: &[Uuid]) -> Result<Vec<Uuid>> {
+        let existing = sqlx::query_scalar::<_, Uuid>("SELECT id FROM contents WHERE id = ANY($1)")
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(existing)
+    }
+
+    pub async fn get_friend_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>> {
+        let friend_ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT CASE WHEN user1_id = $1 THEN user2_id ELSE user1_id END
+            FROM friendships
+            WHERE (user1_id = $1 OR user2_id = $1) AND status = 'accepted'
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(friend_ids)
+    }
+
+    // How often each tag appears on contents the user has liked.
+    pub async fn get_liked_tag_counts(&self, user_id: Uuid) -> Result<Vec<(String, i64)>> {
+        let counts = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT tag, COUNT(*)
+            FROM content_likes l
+            JOIN contents c ON c.id = l.content_id
+            CROSS JOIN LATERAL unnest(c.tags) AS tag
+            WHERE l.user_id = $1
+            GROUP BY tag
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    // Candidate pool for suggestions: the most recent contents the user neither
+    // wrote nor liked, annotated with how many friends liked them. A non-empty
+    // `tags` keeps only contents sharing at least one tag (served by the GIN index).
+    pub async fn get_suggestion_candidates(
+        &self,
+        user_id: Option<Uuid>,
+        friend_ids: &[Uuid],
+        tags: &[String],
+        content_type: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<SuggestionCandidate>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT c.*,
+                COALESCE(fl.friend_likes, 0) AS friend_likes,
+                c.author_id = ANY("#
+        );
+        query
+            .push_bind(friend_ids)
+            .push(
+                r#") AS by_friend
+            FROM contents c
+            LEFT JOIN (
+                SELECT content_id, COUNT(*) AS friend_likes
+                FROM content_likes
+                WHERE user_id = ANY("#
+            )
+            .push_bind(friend_ids)
+            .push(
+                r#")
+                GROUP BY content_id
+            ) fl ON fl.content_id = c.id
+            WHERE TRUE"#
+            );
+        if let Some(user_id) = user_id {
+            query
+                .push(" AND c.author_id <> ")
+                .push_bind(user_id)
+                .push(" AND NOT EXISTS (SELECT 1 FROM content_likes l WHERE l.content_id = c.id AND l.user_id = ")
+                .push_bind(user_id)
+                .push(")");
+        }
+        if !tags.is_empty() {
+            query.push(" AND c.tags && ").push_bind(tags);
+        }
+        if let Some(content_type) = content_type {
+            query.push(" AND c.content_type = ").push_bind(content_type);
+        }
+        query
+            .push(" ORDER BY c.created_at DESC LIMIT ")
+            .push_bind(limit);
+
+        let candidates = query
+            .build_query_as::<SuggestionCandidate>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(candidates)
+    }
+
+    pub async fn create_quiz(
+        &self,
+        content_id: Uuid,
+        created_by: Uuid,
+        draft: &QuizDraft,
+        prompt_version: i32,
+    ) -> Result<Quiz> {
+        let quiz = sqlx::query_as::<_, Quiz>(
+            r#"
+            INSERT INTO quizzes (content_id, created_by, questions, prompt_version)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#
+        )
+        .bind(content_id)
+        .bind(created_by)
+        .bind(sqlx::types::Json(&draft.questions))
+        .bind(prompt_version)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(quiz)
+    }
+
+    pub async fn get_quiz_by_id(&self, id: Uuid) -> Result<Option<Quiz>> {
+        let quiz = sqlx::query_as::<_, Quiz>("SELECT * FROM quizzes WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(quiz)
+    }
+
+    pub async fn create_quiz_submission(
+        &self,
+        quiz_id: Uuid,
+        user_id: Uuid,
+        answers: &[QuizAnswer],
+        results: &[QuestionResult],
+        score: f32,
+        max_score: f32,
+    ) -> Result<QuizSubmission> {
+        let submission = sqlx::query_as::<_, QuizSubmission>(
+            r#"
+            INSERT INTO quiz_submissions (quiz_id, user_id, answers, results, score, max_score)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#
+        )
+        .bind(quiz_id)
+        .bind(user_id)
+        .bind(sqlx::types::Json(answers))
+        .bind(sqlx::types::Json(results))
+        .bind(score)
+        .bind(max_score)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(submission)
+    }
+
+    pub async fn list_quiz_submissions(&self, quiz_id: Uuid, user_id: Uuid, after: Option<&Cursor>, limit: i64) -> Result<Vec<QuizSubmission>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM quiz_submissions WHERE quiz_id = ");
+        query
+            .push_bind(quiz_id)
+            .push(" AND user_id = ")
+            .push_bind(user_id);
+        if let Some(cursor) = after {
+            query
+                .push(" AND (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit);
+
+        let submissions = query
+            .build_query_as::<QuizSubmission>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(submissions)
+    }
+
+    // AI usage: logged-in callers are metered by user, anonymous callers by IP
+    // Counts the subject's usage and inserts the new row in one transaction. The
+    // advisory lock serializes concurrent requests from the same user (or anonymous IP),
+    // so they cannot all pass the check before any of them is recorded. The outer
+    // Result carries database errors, the inner one whatever `admit` rejected with.
+    pub async fn reserve_ai_usage<E>(
+        &self,
+        user_id: Option<Uuid>,
+        ip: &str,
+        endpoint: &str,
+        day_start: DateTime<Utc>,
+        minute_start: DateTime<Utc>,
+        admit: impl FnOnce(&UsageWindow) -> std::result::Result<(), E>,
+    ) -> Result<std::result::Result<Uuid, E>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "SELECT pg_advisory_xact_lock(hashtextextended('ai_usage:' || COALESCE($1::text, 'ip:' || $2), 0))"
+        )
+        .bind(user_id)
+        .bind(ip)
+        .execute(&mut *tx)
+        .await?;
+
+        let window = sqlx::query_as::<_, UsageWindow>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE created_at >= $3) AS day_requests,
+                COALESCE(SUM(prompt_tokens + completion_tokens) FILTER (WHERE created_at >= $3), 0)::BIGINT AS day_tokens,
+                COUNT(*) FILTER (WHERE created_at > $4) AS minute_requests,
+                MIN(created_at) FILTER (WHERE created_at > $4) AS minute_oldest
+            FROM ai_usage
+            WHERE (CASE WHEN $1::uuid IS NULL THEN user_id IS NULL AND ip = $2 ELSE user_id = $1 END)
+              AND created_at >= LEAST($3, $4)
+            "#
+        )
+        .bind(user_id)
+        .bind(ip)
+        .bind(day_start)
+        .bind(minute_start)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if let Err(rejection) = admit(&window) {
+            return Ok(Err(rejection));
+        }
+
+        let id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO ai_usage (user_id, ip, endpoint) VALUES ($1, $2, $3) RETURNING id"
+        )
+        .bind(user_id)
+        .bind(ip)
+        .bind(endpoint)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Ok(id))
+    }
+
+    pub async fn create_ai_usage(&self, user_id: Option<Uuid>, ip: &str, endpoint: &str) -> Result<Uuid> {
+        let id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO ai_usage (user_id, ip, endpoint) VALUES ($1, $2, $3) RETURNING id"
+        )
+        .bind(user_id)
+        .bind(ip)
+        .bind(endpoint)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    pub async fn finish_ai_usage(
+        &self,
+        id: Uuid,
+        prompt_tokens: i64,
+        completion_tokens: i64,
+        prompt: Option<&PromptVersion>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE ai_usage
+            SET prompt_tokens = prompt_tokens + $2,
+                completion_tokens = completion_tokens + $3,
+                prompt_name = COALESCE($4, prompt_name),
+                prompt_version = COALESCE($5, prompt_version)
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(prompt_tokens)
+        .bind(completion_tokens)
+        .bind(prompt.map(|prompt| prompt.name.as_str()))
+        .bind(prompt.map(|prompt| prompt.version))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_ai_usage_by_user(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64) -> Result<Vec<UserUsage>> {
+        let usage = sqlx::query_as::<_, UserUsage>(
+            r#"
+            SELECT a.user_id, u.username,
+                   COUNT(*) AS requests,
+                   COALESCE(SUM(a.prompt_tokens), 0)::BIGINT AS prompt_tokens,
+                   COALESCE(SUM(a.completion_tokens), 0)::BIGINT AS completion_tokens
+            FROM ai_usage a
+            LEFT JOIN users u ON u.id = a.user_id
+            WHERE a.created_at >= $1 AND a.created_at < $2
+            GROUP BY a.user_id, u.username
+            ORDER BY SUM(a.prompt_tokens + a.completion_tokens) DESC, COUNT(*) DESC
+            LIMIT $3
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    pub async fn get_ai_usage_by_endpoint(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<EndpointUsage>> {
+        let usage = sqlx::query_as::<_, EndpointUsage>(
+            r#"
+            SELECT endpoint,
+                   COUNT(*) AS requests,
+                   COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+                   COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens
+            FROM ai_usage
+            WHERE created_at >= $1 AND created_at < $2
+            GROUP BY endpoint
+            ORDER BY requests DESC, endpoint
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    pub async fn get_ai_usage_by_prompt(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<PromptUsage>> {
+        let usage = sqlx::query_as::<_, PromptUsage>(
+            r#"
+            SELECT prompt_name, prompt_version,
+                   COUNT(*) AS requests,
+                   COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+                   COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens
+            FROM ai_usage
+            WHERE created_at >= $1 AND created_at < $2 AND prompt_name IS NOT NULL
+            GROUP BY prompt_name, prompt_version
+            ORDER BY prompt_name, prompt_version
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    // Prompt templates
+    pub async fn list_prompt_templates(&self, name: Option<&str>) -> Result<Vec<PromptTemplate>> {
+        let templates = sqlx::query_as::<_, PromptTemplate>(
+            r#"
+            SELECT * FROM prompt_templates
+            WHERE $1::varchar IS NULL OR name = $1
+            ORDER BY name, version DESC
+            "#
+        )
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(templates)
+    }
+
+    pub async fn get_active_prompt_templates(&self) -> Result<Vec<PromptTemplate>> {
+        let templates = sqlx::query_as::<_, PromptTemplate>(
+            "SELECT * FROM prompt_templates WHERE weight > 0 ORDER BY name, version"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(templates)
+    }
+
+    // The next version number is allocated in the same statement; concurrent creates
+    // for the same prompt surface as a unique violation
+    pub async fn create_prompt_template(
+        &self,
+        created_by: Uuid,
+        name: &str,
+        template: &str,
+        description: Option<&str>,
+        weight: i32,
+    ) -> Result<PromptTemplate> {
+        let prompt = sqlx::query_as::<_, PromptTemplate>(
+            r#"
+            INSERT INTO prompt_templates (name, version, template, description, weight, created_by)
+            SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4, $5
+            FROM prompt_templates WHERE name = $1
+            RETURNING *
+            "#
+        )
+        .bind(name)
+        .bind(template)
+        .bind(description)
+        .bind(weight)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(prompt)
+    }
+
+    pub async fn update_prompt_template_weight(&self, id: Uuid, weight: i32) -> Result<Option<PromptTemplate>> {
+        let prompt = sqlx::query_as::<_, PromptTemplate>(
+            "UPDATE prompt_templates SET weight = $2 WHERE id = $1 RETURNING *"
+        )
+        .bind(id)
+        .bind(weight)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(prompt)
+    }
+
+    // Moderation
+    pub async fn list_moderation_rules(&self) -> Result<Vec<ModerationRule>> {
+        let rules = sqlx::query_as::<_, ModerationRule>(
+            "SELECT * FROM moderation_rules ORDER BY category, created_at, id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rules)
+    }
+
+    pub async fn create_moderation_rule(&self, rule: &CreateModerationRuleRequest) -> Result<ModerationRule> {
+        let rule = sqlx::query_as::<_, ModerationRule>(
+            r#"
+            INSERT INTO moderation_rules (kind, pattern, action, category, description)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#
+        )
+        .bind(rule.kind)
+        .bind(&rule.pattern)
+        .bind(rule.action)
+        .bind(&rule.category)
+        .bind(&rule.description)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(rule)
+    }
+
+    pub async fn update_moderation_rule(&self, id: Uuid, update: &UpdateModerationRuleRequest) -> Result<Option<ModerationRule>> {
+        let rule = sqlx::query_as::<_, ModerationRule>(
+            r#"
+            UPDATE moderation_rules
+            SET action = COALESCE($2, action),
+                category = COALESCE($3, category),
+                description = COALESCE($4, description),
+                enabled = COALESCE($5, enabled)
+            WHERE id = $1
+            RETURNING *
+            "#
+        )
+        .bind(id)
+        .bind(update.action)
+        .bind(&update.category)
+        .bind(&update.description)
+        .bind(update.enabled)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(rule)
+    }
+
+    pub async fn delete_moderation_rule(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM moderation_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn create_moderation_incident(&self, incident: &NewModerationIncident) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO moderation_incidents (user_id, feature, stage, action, source, rule_id, category, excerpt)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#
+        )
+        .bind(incident.user_id)
+        .bind(incident.feature)
+        .bind(incident.stage)
+        .bind(incident.action)
+        .bind(incident.source)
+        .bind(incident.rule_id)
+        .bind(&incident.category)
+        .bind(&incident.excerpt)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_moderation_incidents(
+        &self,
+        stage: Option<ModerationStage>,
+        after: Option<&Cursor>,
+        limit: i64,
+    ) -> Result<Vec<ModerationIncident>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM moderation_incidents WHERE TRUE");
+        if let Some(stage) = stage {
+            query.push(" AND stage = ").push_bind(stage);
+        }
+        if let Some(cursor) = after {
+            query
+                .push(" AND (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit);
+
+        let incidents = query
+            .build_query_as::<ModerationIncident>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(incidents)
     }
 }
@@ -0,0 +1,212 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use validator::ValidationErrors;
+
+use crate::validation;
+
+// 业务错误码，取值与 docs/API接口设计.md §15 保持一致；表中标为预留的错误码在对应功能实现时再加入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    UsernameTaken = 10001,
+    EmailTaken = 10002,
+    InvalidCredentials = 10003,
+    TokenExpired = 10004,
+    TokenInvalid = 10005,
+    ContentNotFound = 20001,
+    ContentForbidden = 20002,
+    AiUnavailable = 40001,
+    RateLimited = 40002,
+    InputRejected = 40003,
+    OutputRejected = 40004,
+}
+
+impl ErrorCode {
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::UsernameTaken | ErrorCode::EmailTaken => StatusCode::CONFLICT,
+            ErrorCode::InvalidCredentials | ErrorCode::TokenExpired | ErrorCode::TokenInvalid => {
+                StatusCode::UNAUTHORIZED
+            }
+            ErrorCode::ContentNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::ContentForbidden => StatusCode::FORBIDDEN,
+            ErrorCode::AiUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::InputRejected | ErrorCode::OutputRejected => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            ErrorCode::UsernameTaken => "用户名已存在",
+            ErrorCode::EmailTaken => "邮箱已注册",
+            ErrorCode::InvalidCredentials => "用户名或密码错误",
+            ErrorCode::TokenExpired => "Token已过期",
+            ErrorCode::TokenInvalid => "Token无效",
+            ErrorCode::ContentNotFound => "内容不存在",
+            ErrorCode::ContentForbidden => "无权限操作此内容",
+            ErrorCode::AiUnavailable => "AI服务暂时不可用",
+            ErrorCode::RateLimited => "请求频率过高",
+            ErrorCode::InputRejected => "提问内容不符合社区规范",
+            ErrorCode::OutputRejected => "AI回复未通过内容安全审核",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AppError {
+    // 带有业务错误码的错误，状态码和提示信息由错误码决定
+    Coded(ErrorCode),
+    BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    // 请求体字段校验失败，响应中附带逐字段的错误信息
+    Validation(ValidationErrors),
+    PayloadTooLarge,
+    // 超出调用频率或配额，响应头 Retry-After 给出可重试的秒数
+    RateLimited { message: String, retry_after: u64 },
+    // 内部错误只记录日志，不向客户端暴露细节
+    Internal(anyhow::Error),
+}
+
+impl AppError {
+    // 上游 AI 服务出错时记录原因，对客户端统一返回 40001
+    pub fn ai_unavailable(err: anyhow::Error) -> Self {
+        tracing::warn!("AI provider error: {:?}", err);
+        AppError::Coded(ErrorCode::AiUnavailable)
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::Coded(code) => code.status(),
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::RateLimited { .. } => ErrorCode::RateLimited.status(),
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            AppError::Coded(ErrorCode::InputRejected | ErrorCode::OutputRejected) => "moderation",
+            AppError::Coded(code) => match code.status() {
+                StatusCode::CONFLICT => "conflict",
+                StatusCode::UNAUTHORIZED => "unauthorized",
+                StatusCode::FORBIDDEN => "forbidden",
+                StatusCode::NOT_FOUND => "not_found",
+                StatusCode::TOO_MANY_REQUESTS => "rate_limited",
+                StatusCode::SERVICE_UNAVAILABLE => "unavailable",
+                _ => "bad_request",
+            },
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::Validation(_) => "validation",
+            AppError::PayloadTooLarge => "payload_too_large",
+            AppError::RateLimited { .. } => "rate_limited",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            AppError::Coded(code) => code.message().to_string(),
+            AppError::BadRequest(message)
+            | AppError::Unauthorized(message)
+            | AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::RateLimited { message, .. } => message.clone(),
+            AppError::Validation(_) => "请求参数校验失败".to_string(),
+            AppError::PayloadTooLarge => "请求体过大".to_string(),
+            AppError::Internal(_) => "服务器内部错误".to_string(),
+        }
+    }
+
+    fn code(&self) -> Option<u32> {
+        match self {
+            AppError::Coded(code) => Some(*code as u32),
+            AppError::RateLimited { .. } => Some(ErrorCode::RateLimited as u32),
+            _ => None,
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::Internal(err) = &self {
+            tracing::error!("internal error: {:?}", err);
+        }
+
+        let mut body = json!({
+            "success": false,
+            "code": self.code(),
+            "message": self.message(),
+            "error": self.kind(),
+            "timestamp": chrono::Utc::now(),
+        });
+        if let AppError::Validation(errors) = &self {
+            body["details"] = json!(validation::field_errors(errors));
+        }
+
+        if let AppError::RateLimited { retry_after, .. } = &self {
+            body["retry_after"] = json!(retry_after);
+            return (
+                self.status(),
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(body),
+            )
+                .into_response();
+        }
+
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+// PostgreSQL 错误码：23505 唯一约束冲突，23503 外键约束冲突
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        if let sqlx::Error::Database(db_err) = &err {
+            match db_err.code().as_deref() {
+                Some("23505") => {
+                    return match db_err.constraint() {
+                        Some("users_username_key") => AppError::Coded(ErrorCode::UsernameTaken),
+                        Some("users_email_key") => AppError::Coded(ErrorCode::EmailTaken),
+                        _ => AppError::Conflict("资源已存在".to_string()),
+                    };
+                }
+                Some("23503") => return AppError::NotFound("关联的资源不存在".to_string()),
+                _ => {}
+            }
+        }
+
+        AppError::Internal(err.into())
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast::<sqlx::Error>() {
+            Ok(sqlx_err) => sqlx_err.into(),
+            Err(err) => AppError::Internal(err),
+        }
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(errors)
+    }
+}
@@ -0,0 +1,221 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::ai::moderation::compile_rule;
+use crate::ai::prompts::{self, PROMPTS};
+use crate::ai::{Moderator, PromptRegistry};
+use crate::auth::{authz, CurrentUser};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::models::{
+    page_limit, parse_cursor, AiUsageQuery, AiUsageReport, CreateModerationRuleRequest, CreatePromptTemplateRequest,
+    Cursor, ModerationIncident, ModerationIncidentListQuery, ModerationRule, Page, PromptOverview,
+    PromptTemplateListQuery, UpdateModerationRuleRequest, UpdatePromptTemplateRequest,
+};
+use crate::validation::{ValidatedJson, ValidatedQuery};
+
+// AI 用量统计，按用户和接口汇总，默认统计当天（UTC）
+pub async fn get_ai_usage(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedQuery(query): ValidatedQuery<AiUsageQuery>,
+) -> Result<Json<AiUsageReport>, AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+
+    let today = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let from = query.from.unwrap_or(today);
+    let to = query.to.unwrap_or(today + Duration::days(1));
+    if from >= to {
+        return Err(AppError::BadRequest("统计区间的开始时间必须早于结束时间".to_string()));
+    }
+
+    let users = db.get_ai_usage_by_user(from, to, query.limit.unwrap_or(50)).await?;
+    let endpoints = db.get_ai_usage_by_endpoint(from, to).await?;
+    let prompts = db.get_ai_usage_by_prompt(from, to).await?;
+
+    Ok(Json(AiUsageReport { from, to, users, endpoints, prompts }))
+}
+
+// 修改启用状态后立即刷新本实例的缓存，其他实例在定时刷新后生效
+async fn reload_prompts(prompts: &PromptRegistry) {
+    if let Err(err) = prompts.reload().await {
+        tracing::warn!("failed to reload prompt templates: {:?}", err);
+    }
+}
+
+pub async fn get_prompt_templates(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedQuery(query): ValidatedQuery<PromptTemplateListQuery>,
+) -> Result<Json<Vec<PromptOverview>>, AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+
+    let mut templates = db.list_prompt_templates(query.name.as_deref()).await?;
+    let overview = PROMPTS
+        .into_iter()
+        .filter(|spec| query.name.as_deref().is_none_or(|name| name == spec.name))
+        .map(|spec| PromptOverview {
+            name: spec.name,
+            variables: spec.variables,
+            builtin: spec.builtin,
+            versions: templates.extract_if(.., |template| template.name == spec.name).collect(),
+        })
+        .collect();
+
+    Ok(Json(overview))
+}
+
+// 新建一个版本；版本内容不可修改，需要调整时创建新版本
+pub async fn create_prompt_template(
+    State(db): State<Database>,
+    State(prompts): State<PromptRegistry>,
+    current_user: CurrentUser,
+    ValidatedJson(payload): ValidatedJson<CreatePromptTemplateRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+
+    let spec = prompts::find_spec(&payload.name)
+        .ok_or_else(|| AppError::BadRequest(format!("未知的提示词: {}", payload.name)))?;
+    let unknown = prompts::unknown_variables(spec, &payload.template);
+    if !unknown.is_empty() {
+        let available = if spec.variables.is_empty() { "无".to_string() } else { spec.variables.join(", ") };
+        return Err(AppError::BadRequest(format!(
+            "模板中包含不支持的变量: {}（可用变量: {}）",
+            unknown.join(", "),
+            available
+        )));
+    }
+
+    let template = db
+        .create_prompt_template(
+            current_user.id,
+            spec.name,
+            &payload.template,
+            payload.description.as_deref(),
+            payload.weight.unwrap_or(0),
+        )
+        .await?;
+    if template.weight > 0 {
+        reload_prompts(&prompts).await;
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "提示词版本创建成功", "prompt": template }))
+    ))
+}
+
+// 调整流量权重：0 为停用，多个版本同时启用时按权重分配流量
+pub async fn update_prompt_template(
+    State(db): State<Database>,
+    State(prompts): State<PromptRegistry>,
+    current_user: CurrentUser,
+    Path(template_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdatePromptTemplateRequest>,
+) -> Result<Json<Value>, AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+
+    let template = db
+        .update_prompt_template_weight(template_id, payload.weight)
+        .await?
+        .ok_or_else(|| AppError::NotFound("提示词版本不存在".to_string()))?;
+    reload_prompts(&prompts).await;
+
+    Ok(Json(json!({ "message": "提示词版本更新成功", "prompt": template })))
+}
+
+async fn reload_moderation_rules(moderator: &Moderator) {
+    if let Err(err) = moderator.reload().await {
+        tracing::warn!("failed to reload moderation rules: {:?}", err);
+    }
+}
+
+pub async fn get_moderation_rules(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<ModerationRule>>, AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+
+    Ok(Json(db.list_moderation_rules().await?))
+}
+
+pub async fn create_moderation_rule(
+    State(db): State<Database>,
+    State(moderator): State<Moderator>,
+    current_user: CurrentUser,
+    ValidatedJson(payload): ValidatedJson<CreateModerationRuleRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+
+    compile_rule(payload.kind, &payload.pattern)
+        .map_err(|err| AppError::BadRequest(format!("规则无效: {}", err)))?;
+
+    let rule = db.create_moderation_rule(&payload).await?;
+    reload_moderation_rules(&moderator).await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "审核规则创建成功", "rule": rule }))
+    ))
+}
+
+pub async fn update_moderation_rule(
+    State(db): State<Database>,
+    State(moderator): State<Moderator>,
+    current_user: CurrentUser,
+    Path(rule_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateModerationRuleRequest>,
+) -> Result<Json<Value>, AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+
+    let rule = db
+        .update_moderation_rule(rule_id, &payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("审核规则不存在".to_string()))?;
+    reload_moderation_rules(&moderator).await;
+
+    Ok(Json(json!({ "message": "审核规则更新成功", "rule": rule })))
+}
+
+pub async fn delete_moderation_rule(
+    State(db): State<Database>,
+    State(moderator): State<Moderator>,
+    current_user: CurrentUser,
+    Path(rule_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+
+    if !db.delete_moderation_rule(rule_id).await? {
+        return Err(AppError::NotFound("审核规则不存在".to_string()));
+    }
+    reload_moderation_rules(&moderator).await;
+
+    Ok(Json(json!({ "message": "审核规则删除成功" })))
+}
+
+pub async fn get_moderation_incidents(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedQuery(query): ValidatedQuery<ModerationIncidentListQuery>,
+) -> Result<Json<Page<ModerationIncident>>, AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    let incidents = db
+        .list_moderation_incidents(query.stage, after.as_ref(), limit + 1)
+        .await?;
+    let page = Page::from_rows(incidents, limit, |incident| Cursor {
+        created_at: incident.created_at,
+        id: incident.id,
+        likes_count: None,
+    });
+
+    Ok(Json(page))
+}
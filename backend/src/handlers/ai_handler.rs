@@ -1,98 +1,572 @@
+use std::{collections::HashSet, convert::Infallible};
+
 use axum::{
+    extract::State,
     http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
-use serde::{Deserialize, Serialize};
+use futures::stream::{self, Stream, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 
-#[derive(Debug, Deserialize)]
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::ai::history::{truncate_history, HISTORY_TOKEN_BUDGET};
+use crate::ai::prompts::{CHAT_PROMPT, QUIZ_PROMPT, STUDY_PLAN_PROMPT, SUGGESTION_PROMPT, SYSTEM_PROMPT};
+use crate::ai::retrieval::RETRIEVAL_TOP_K;
+use crate::ai::{ChatMessage, Citation, ContentIndex, DeltaStream, Moderator, PromptRegistry, RetrievedChunk, SharedAiProvider, StreamModerator};
+use crate::auth::CurrentUser;
+use crate::database::Database;
+use crate::error::{AppError, ErrorCode};
+use crate::models::{ModerationAction, ModerationStage, PlanDocument, PromptVersion, QuizDraft, QuizRequest, QuizResponse, StudyPlanRequest, QUIZ_DRAFT_SCHEMA, Suggestion, SuggestionRequest, SuggestionResponse, PLAN_DOCUMENT_SCHEMA};
+use crate::recommendation::{self, UserProfile};
+use crate::validation::ValidatedJson;
+
+#[derive(Debug, Deserialize, Validate)]
 pub struct ChatRequest {
+    #[validate(
+        length(min = 1, max = 2000, message = "问题长度必须在 1 到 2000 个字符之间"),
+        custom(function = "crate::validation::not_blank")
+    )]
     pub question: String,
+    #[validate(length(max = 10000, message = "补充资料不能超过 10000 个字符"))]
     pub context: Option<String>,
+    // 指定后会附带该会话的历史对话，并把本轮问答写入会话（需要登录）
+    pub conversation_id: Option<Uuid>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct StudyPlanRequest {
-    pub subject: String,
-    pub level: String,
-    pub goals: String,
-}
 
 #[derive(Debug, Serialize)]
 pub struct ChatResponse {
     pub question: String,
     pub answer: String,
+    pub conversation_id: Option<Uuid>,
+    // 回答参考的平台内容
+    pub citations: Vec<Citation>,
+    // 本次回答使用的提示词版本
+    pub prompt: PromptVersion,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+// 生成的计划尚未保存，客户端确认后通过 POST /api/study-plans 保存
 #[derive(Debug, Serialize)]
-pub struct StudyPlanResponse {
+pub struct GeneratedStudyPlan {
     pub subject: String,
     pub level: String,
     pub goals: String,
-    pub plan: String,
+    pub plan: PlanDocument,
+    pub total_hours: f32,
+    pub prompt: PromptVersion,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
-// 模拟 AI 服务，实际项目中应调用真实的 AI API
-async fn ask_ai(question: &str) -> String {
-    // 这里是模拟的 AI 响应，实际应调用 OpenAI、Claude 等 API
-    format!("这是对问题「{}」的AI回答。在实际项目中，这里会调用真实的AI服务。", question)
+// 内容安全事件中记录的功能名称
+const CHAT_FEATURE: &str = "chat";
+const STUDY_PLAN_FEATURE: &str = "study_plan";
+const SUGGESTION_FEATURE: &str = "suggestions";
+const QUIZ_FEATURE: &str = "quiz";
+
+fn build_messages(
+    system_prompt: &str,
+    question: &str,
+    context: Option<&str>,
+    passages: &[RetrievedChunk],
+    history: Vec<ChatMessage>,
+) -> Vec<ChatMessage> {
+    let mut messages = vec![ChatMessage::system(system_prompt)];
+    if let Some(context) = context.filter(|c| !c.trim().is_empty()) {
+        messages.push(ChatMessage::system(format!("参考资料：\n{}", context)));
+    }
+    if !passages.is_empty() {
+        let references: Vec<String> = passages
+            .iter()
+            .enumerate()
+            .map(|(i, passage)| format!("[{}]《{}》\n{}", i + 1, passage.title, passage.body))
+            .collect();
+        messages.push(ChatMessage::system(format!(
+            "以下是平台上与问题相关的内容，回答时请优先参考，并用 [编号] 标注引用：\n\n{}",
+            references.join("\n\n")
+        )));
+    }
+    messages.extend(truncate_history(history, HISTORY_TOKEN_BUDGET));
+    messages.push(ChatMessage::user(question));
+    messages
+}
+
+async fn ask_ai(ai: &SharedAiProvider, messages: &[ChatMessage]) -> Result<String, AppError> {
+    ai.chat(messages).await.map_err(AppError::ai_unavailable)
+}
+
+// 检索失败时仍然正常回答，只是不附带参考资料
+async fn retrieve_passages(index: &ContentIndex, question: &str) -> Vec<RetrievedChunk> {
+    index.search(question, RETRIEVAL_TOP_K).await.unwrap_or_else(|err| {
+        tracing::warn!("content retrieval failed: {:?}", err);
+        Vec::new()
+    })
+}
+
+// 读取会话历史；会话只对创建者可见，其他人访问视为不存在
+async fn load_history(
+    db: &Database,
+    current_user: Option<&CurrentUser>,
+    conversation_id: Option<Uuid>,
+) -> Result<Vec<ChatMessage>, AppError> {
+    let Some(conversation_id) = conversation_id else {
+        return Ok(Vec::new());
+    };
+    let current_user = current_user
+        .ok_or_else(|| AppError::Unauthorized("使用会话需要登录".to_string()))?;
+
+    let conversation = db
+        .get_ai_conversation_by_id(conversation_id)
+        .await?
+        .filter(|conversation| conversation.user_id == current_user.id)
+        .ok_or_else(|| AppError::NotFound("会话不存在".to_string()))?;
+
+    let messages = db.get_ai_messages(conversation.id).await?;
+    Ok(messages.into_iter().map(ChatMessage::from).collect())
+}
+
+// 问题和补充资料先经过内容安全审核，命中脱敏规则的片段在发给模型前替换掉
+async fn moderate_request(
+    moderator: &Moderator,
+    user_id: Option<Uuid>,
+    payload: &ChatRequest,
+) -> Result<(String, Option<String>), AppError> {
+    let question = moderator
+        .check(user_id, CHAT_FEATURE, ModerationStage::Input, &payload.question)
+        .await?;
+    let context = match payload.context.as_deref() {
+        Some(context) => Some(moderator.check(user_id, CHAT_FEATURE, ModerationStage::Input, context).await?),
+        None => None,
+    };
+
+    Ok((question, context))
 }
 
 pub async fn chat_with_ai(
-    Json(payload): Json<ChatRequest>,
-) -> Result<Json<ChatResponse>, StatusCode> {
-    if payload.question.trim().is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+    State(ai): State<SharedAiProvider>,
+    State(db): State<Database>,
+    State(index): State<ContentIndex>,
+    State(prompts): State<PromptRegistry>,
+    State(moderator): State<Moderator>,
+    current_user: Option<CurrentUser>,
+    ValidatedJson(payload): ValidatedJson<ChatRequest>,
+) -> Result<Json<ChatResponse>, AppError> {
+    let user_id = current_user.as_ref().map(|user| user.id);
+    let (question, context) = moderate_request(&moderator, user_id, &payload).await?;
+
+    let history = load_history(&db, current_user.as_ref(), payload.conversation_id).await?;
+    let passages = retrieve_passages(&index, &question).await;
+    let prompt = prompts.render(&CHAT_PROMPT, user_id, &[]);
+    let messages = build_messages(&prompt.text, &question, context.as_deref(), &passages, history);
+    let answer = ask_ai(&ai, &messages).await?;
+    let answer = moderator
+        .check(user_id, CHAT_FEATURE, ModerationStage::Output, &answer)
+        .await?;
+
+    if let Some(conversation_id) = payload.conversation_id {
+        db.append_ai_turn(conversation_id, &question, &answer).await?;
     }
 
-    let answer = ask_ai(&payload.question).await;
-    
     Ok(Json(ChatResponse {
-        question: payload.question,
+        question,
         answer,
+        conversation_id: payload.conversation_id,
+        citations: passages.iter().map(RetrievedChunk::citation).collect(),
+        prompt: prompt.version,
         timestamp: chrono::Utc::now(),
     }))
 }
 
+// 流式输出的状态；连接断开时 axum 会丢弃响应流，上游请求随之取消
+struct ChatStream {
+    deltas: DeltaStream,
+    // 累积完整回答，检查拦截规则，并决定哪些部分已可以脱敏后发出
+    output: StreamModerator,
+    finished: bool,
+    // 完整生成后写入会话：(数据库, 会话 ID, 用户问题)
+    conversation: Option<(Database, Uuid, String)>,
+    citations: Vec<Citation>,
+    prompt: PromptVersion,
+    moderator: Moderator,
+    user_id: Option<Uuid>,
+}
+
+impl Drop for ChatStream {
+    fn drop(&mut self) {
+        if !self.finished {
+            tracing::debug!("client disconnected, AI stream cancelled after {} chars", self.output.text().chars().count());
+        }
+    }
+}
+
+fn error_event(code: ErrorCode) -> Result<Event, axum::Error> {
+    Event::default()
+        .event("error")
+        .json_data(json!({ "code": code as u32, "message": code.message() }))
+}
+
+fn delta_event(content: &str) -> Result<Event, axum::Error> {
+    Event::default().event("delta").json_data(json!({ "content": content }))
+}
+
+fn sse_events(state: ChatStream) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(state, |mut state| async move {
+        if state.finished {
+            return None;
+        }
+
+        let events = match state.deltas.next().await {
+            // 每收到一段就检查新生成的内容，命中拦截规则时立即中止
+            Some(Ok(delta)) => match state.output.push(&delta) {
+                // 末尾可能是尚未生成完整的命中，暂不发送
+                Ok(released) if released.is_empty() => Vec::new(),
+                Ok(released) => vec![delta_event(&released)],
+                Err(hit) => {
+                    state.finished = true;
+                    state
+                        .moderator
+                        .record_hit(state.user_id, CHAT_FEATURE, ModerationStage::Output, ModerationAction::Block, hit)
+                        .await;
+                    vec![error_event(ErrorCode::OutputRejected)]
+                }
+            },
+            Some(Err(err)) => {
+                state.finished = true;
+                tracing::warn!("AI provider stream error: {:?}", err);
+                vec![error_event(ErrorCode::AiUnavailable)]
+            }
+            // 完整回答再做一次审核（含脱敏和分类器），done 中的 answer 以审核后的文本为准
+            None => {
+                state.finished = true;
+                match state
+                    .moderator
+                    .check(state.user_id, CHAT_FEATURE, ModerationStage::Output, state.output.text())
+                    .await
+                {
+                    Ok(answer) => {
+                        let mut conversation_id = None;
+                        if let Some((db, id, question)) = &state.conversation {
+                            conversation_id = Some(*id);
+                            if let Err(err) = db.append_ai_turn(*id, question, &answer).await {
+                                tracing::error!("failed to save AI conversation turn: {:?}", err);
+                            }
+                        }
+                        let done = Event::default().event("done").json_data(json!({
+                            "answer": answer,
+                            "conversation_id": conversation_id,
+                            "citations": state.citations,
+                            "prompt": state.prompt,
+                            "timestamp": chrono::Utc::now(),
+                        }));
+                        // 审核通过后才发出暂缓的末尾部分
+                        match state.output.finish() {
+                            rest if rest.is_empty() => vec![done],
+                            rest => vec![delta_event(&rest), done],
+                        }
+                    }
+                    Err(_) => vec![error_event(ErrorCode::OutputRejected)],
+                }
+            }
+        };
+
+        let events: Vec<Result<Event, Infallible>> = events
+            .into_iter()
+            .map(|event| Ok(event.expect("SSE payload is valid JSON")))
+            .collect();
+        Some((stream::iter(events), state))
+    })
+    .flatten()
+}
+
+// 以 SSE 逐段返回回答：delta 为增量文本，done 携带完整回答，error 表示上游中途失败或回答未通过审核
+pub async fn chat_with_ai_stream(
+    State(ai): State<SharedAiProvider>,
+    State(db): State<Database>,
+    State(index): State<ContentIndex>,
+    State(prompts): State<PromptRegistry>,
+    State(moderator): State<Moderator>,
+    current_user: Option<CurrentUser>,
+    ValidatedJson(payload): ValidatedJson<ChatRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let user_id = current_user.as_ref().map(|user| user.id);
+    let (question, context) = moderate_request(&moderator, user_id, &payload).await?;
+
+    let history = load_history(&db, current_user.as_ref(), payload.conversation_id).await?;
+    let passages = retrieve_passages(&index, &question).await;
+    let prompt = prompts.render(&CHAT_PROMPT, user_id, &[]);
+    let messages = build_messages(&prompt.text, &question, context.as_deref(), &passages, history);
+    let deltas = ai.chat_stream(&messages).await.map_err(AppError::ai_unavailable)?;
+
+    let state = ChatStream {
+        deltas,
+        output: moderator.stream_moderator(),
+        finished: false,
+        conversation: payload.conversation_id.map(|conversation_id| (db, conversation_id, question)),
+        citations: passages.iter().map(RetrievedChunk::citation).collect(),
+        prompt: prompt.version,
+        moderator,
+        user_id,
+    };
+
+    Ok(Sse::new(sse_events(state)).keep_alive(KeepAlive::default()))
+}
+
+// 模型输出不合法时把错误反馈给模型重试一次
+const STRUCTURED_OUTPUT_ATTEMPTS: usize = 2;
+
+fn parse_structured<T: DeserializeOwned + Validate>(value: Value) -> Result<T, String> {
+    let output: T = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    output.validate().map_err(|e| e.to_string())?;
+    Ok(output)
+}
+
+// 按 schema 生成结构化结果，并用对应类型的反序列化和字段校验把关
+async fn generate_structured<T: DeserializeOwned + Validate>(
+    ai: &SharedAiProvider,
+    mut messages: Vec<ChatMessage>,
+    name: &str,
+    schema: &Value,
+) -> Result<T, AppError> {
+    for _ in 0..STRUCTURED_OUTPUT_ATTEMPTS {
+        let value = ai
+            .chat_json(&messages, name, schema)
+            .await
+            .map_err(AppError::ai_unavailable)?;
+
+        match parse_structured(value.clone()) {
+            Ok(output) => return Ok(output),
+            Err(reason) => {
+                tracing::warn!("model returned an invalid {}: {}", name, reason);
+                messages.push(ChatMessage::assistant(value.to_string()));
+                messages.push(ChatMessage::user(format!(
+                    "上面的输出不符合要求：{}。请修正后重新输出完整的 JSON。",
+                    reason
+                )));
+            }
+        }
+    }
+
+    Err(AppError::ai_unavailable(anyhow::anyhow!("model did not return a valid {}", name)))
+}
+
+// 结构化结果逐个文本字段做输出审核，ID 等字段不参与，脱敏后不会破坏结构
+async fn moderate_output(
+    moderator: &Moderator,
+    user_id: Option<Uuid>,
+    feature: &'static str,
+    texts: Vec<&mut String>,
+) -> Result<(), AppError> {
+    for text in texts {
+        *text = moderator.check(user_id, feature, ModerationStage::Output, text).await?;
+    }
+    Ok(())
+}
+
 pub async fn get_study_plan(
-    Json(payload): Json<StudyPlanRequest>,
-) -> Result<Json<StudyPlanResponse>, StatusCode> {
-    let prompt = format!(
-        "为{}水平的学习者制定{}学习计划，目标：{}",
-        payload.level, payload.subject, payload.goals
+    State(ai): State<SharedAiProvider>,
+    State(db): State<Database>,
+    State(index): State<ContentIndex>,
+    State(prompts): State<PromptRegistry>,
+    State(moderator): State<Moderator>,
+    current_user: Option<CurrentUser>,
+    ValidatedJson(mut payload): ValidatedJson<StudyPlanRequest>,
+) -> Result<Json<GeneratedStudyPlan>, AppError> {
+    let user_id = current_user.map(|user| user.id);
+    payload.subject = moderator
+        .check(user_id, STUDY_PLAN_FEATURE, ModerationStage::Input, &payload.subject)
+        .await?;
+    payload.goals = moderator
+        .check(user_id, STUDY_PLAN_FEATURE, ModerationStage::Input, &payload.goals)
+        .await?;
+
+    let prompt = prompts.render(
+        &STUDY_PLAN_PROMPT,
+        user_id,
+        &[
+            ("subject", payload.subject.clone()),
+            ("level", payload.level.clone()),
+            ("goals", payload.goals.clone()),
+            ("weeks", payload.weeks.map_or("未指定".to_string(), |weeks| format!("{} 周", weeks))),
+            ("hours_per_week", payload.hours_per_week.map_or("未指定".to_string(), |hours| format!("{} 小时", hours))),
+            ("schema", PLAN_DOCUMENT_SCHEMA.to_string()),
+        ],
     );
-    
-    let plan = ask_ai(&prompt).await;
-    
-    Ok(Json(StudyPlanResponse {
+
+    let mut messages = vec![ChatMessage::system(SYSTEM_PROMPT)];
+    let passages = retrieve_passages(&index, &format!("{} {}", payload.subject, payload.goals)).await;
+    if !passages.is_empty() {
+        let references: Vec<String> = passages
+            .iter()
+            .map(|passage| format!("content_id: {}\n《{}》\n{}", passage.content_id, passage.title, passage.body))
+            .collect();
+        messages.push(ChatMessage::system(format!("平台内容：\n\n{}", references.join("\n\n"))));
+    }
+    messages.push(ChatMessage::user(prompt.text));
+
+    let mut plan: PlanDocument = generate_structured(&ai, messages, "study_plan", &PLAN_DOCUMENT_SCHEMA).await?;
+    moderate_output(&moderator, user_id, STUDY_PLAN_FEATURE, plan.texts_mut()).await?;
+
+    // 模型可能编造内容 ID，只保留平台上真实存在的内容
+    let existing = db.get_existing_content_ids(&plan.content_ids()).await?;
+    for task in plan.tasks_mut() {
+        task.content_ids.retain(|id| existing.contains(id));
+    }
+
+    Ok(Json(GeneratedStudyPlan {
         subject: payload.subject,
         level: payload.level,
         goals: payload.goals,
+        total_hours: plan.total_hours(),
         plan,
+        prompt: prompt.version,
         timestamp: chrono::Utc::now(),
     }))
 }
 
-pub async fn get_learning_suggestions(
-    Json(payload): Json<Value>,
-) -> Result<Json<Value>, StatusCode> {
-    let interests = payload.get("interests")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
+// 送给模型的正文长度上限
+const QUIZ_SOURCE_CHARS: usize = 6000;
+
+pub async fn generate_quiz(
+    State(ai): State<SharedAiProvider>,
+    State(db): State<Database>,
+    State(prompts): State<PromptRegistry>,
+    State(moderator): State<Moderator>,
+    current_user: CurrentUser,
+    ValidatedJson(payload): ValidatedJson<QuizRequest>,
+) -> Result<(StatusCode, Json<QuizResponse>), AppError> {
+    let content = db
+        .get_content_by_id(payload.content_id)
+        .await?
+        .ok_or(AppError::Coded(ErrorCode::ContentNotFound))?;
+
+    let multiple_choice = payload.multiple_choice.unwrap_or(3);
+    let short_answer = payload.short_answer.unwrap_or(2);
+    if multiple_choice + short_answer == 0 {
+        return Err(AppError::BadRequest("至少需要一道题目".to_string()));
+    }
+
+    let body: String = content.body.chars().take(QUIZ_SOURCE_CHARS).collect();
+    let prompt = prompts.render(
+        &QUIZ_PROMPT,
+        Some(current_user.id),
+        &[
+            ("multiple_choice", multiple_choice.to_string()),
+            ("short_answer", short_answer.to_string()),
+            ("title", content.title.clone()),
+            ("body", body),
+            ("schema", QUIZ_DRAFT_SCHEMA.to_string()),
+        ],
+    );
+    let messages = vec![ChatMessage::system(SYSTEM_PROMPT), ChatMessage::user(prompt.text)];
+
+    let mut draft: QuizDraft = generate_structured(&ai, messages, "quiz", &QUIZ_DRAFT_SCHEMA).await?;
+    moderate_output(&moderator, Some(current_user.id), QUIZ_FEATURE, draft.texts_mut()).await?;
+    let quiz = db
+        .create_quiz(content.id, current_user.id, &draft, prompt.version.version)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(quiz.into())))
+}
+
+// 候选池大小和默认推荐数量
+const SUGGESTION_CANDIDATES: i64 = 500;
+const DEFAULT_SUGGESTIONS: usize = 10;
+
+// 解释只是附加信息，AI 服务不可用时照常返回推荐结果
+async fn explain_suggestions(
+    ai: &SharedAiProvider,
+    prompts: &PromptRegistry,
+    moderator: &Moderator,
+    user_id: Option<Uuid>,
+    interests: &[String],
+    suggestions: &[Suggestion],
+) -> Option<(String, PromptVersion)> {
+    if suggestions.is_empty() {
+        return None;
+    }
+
+    let picks: Vec<String> = suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, suggestion)| {
+            let mut line = format!("{}. 《{}》（标签：{}）", i + 1, suggestion.content.title, suggestion.content.tags.join("、"));
+            if !suggestion.reasons.is_empty() {
+                line.push_str(&format!("，推荐依据：{}", suggestion.reasons.join("；")));
+            }
+            line
         })
-        .unwrap_or_else(|| "通用知识".to_string());
-
-    let suggestions = ask_ai(&format!("为对{}感兴趣的学习者推荐学习内容", interests)).await;
-    
-    Ok(Json(json!({
-        "interests": interests,
-        "suggestions": suggestions,
-        "timestamp": chrono::Utc::now()
-    })))
+        .collect();
+    let prompt = prompts.render(
+        &SUGGESTION_PROMPT,
+        user_id,
+        &[("interests", interests.join("、")), ("picks", picks.join("\n"))],
+    );
+    let messages = vec![ChatMessage::system(SYSTEM_PROMPT), ChatMessage::user(prompt.text)];
+
+    let explanation = ai
+        .chat(&messages)
+        .await
+        .inspect_err(|err| tracing::warn!("failed to explain suggestions: {:?}", err))
+        .ok()?;
+    // 未通过审核的解释直接丢弃，事件已记录
+    let explanation = moderator
+        .check(user_id, SUGGESTION_FEATURE, ModerationStage::Output, &explanation)
+        .await
+        .ok()?;
+
+    Some((explanation, prompt.version))
+}
+
+// 按兴趣标签、点赞历史和好友动态对平台内容排序；未登录时只使用兴趣标签和热度
+pub async fn get_learning_suggestions(
+    State(ai): State<SharedAiProvider>,
+    State(db): State<Database>,
+    State(prompts): State<PromptRegistry>,
+    State(moderator): State<Moderator>,
+    current_user: Option<CurrentUser>,
+    ValidatedJson(payload): ValidatedJson<SuggestionRequest>,
+) -> Result<Json<SuggestionResponse>, AppError> {
+    let user_id = current_user.map(|user| user.id);
+    let (friend_ids, liked_tags) = match user_id {
+        Some(user_id) => (db.get_friend_ids(user_id).await?, db.get_liked_tag_counts(user_id).await?),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let content_type = payload.content_type.as_deref();
+    // 先取带有兴趣标签的内容，不足候选池大小时再用最新的内容补足
+    let mut candidates = if payload.interests.is_empty() {
+        Vec::new()
+    } else {
+        let tags = recommendation::tag_variants(&payload.interests);
+        db.get_suggestion_candidates(user_id, &friend_ids, &tags, content_type, SUGGESTION_CANDIDATES)
+            .await?
+    };
+    if (candidates.len() as i64) < SUGGESTION_CANDIDATES {
+        let seen: HashSet<Uuid> = candidates.iter().map(|candidate| candidate.content.id).collect();
+        let recent = db
+            .get_suggestion_candidates(user_id, &friend_ids, &[], content_type, SUGGESTION_CANDIDATES)
+            .await?;
+        candidates.extend(recent.into_iter().filter(|candidate| !seen.contains(&candidate.content.id)));
+    }
+    let profile = UserProfile::new(&payload.interests, liked_tags);
+    let suggestions = recommendation::rank(&profile, candidates, payload.limit.unwrap_or(DEFAULT_SUGGESTIONS));
+
+    let explained = if payload.explain {
+        explain_suggestions(&ai, &prompts, &moderator, user_id, &payload.interests, &suggestions).await
+    } else {
+        None
+    };
+    let (explanation, prompt) = explained.unzip();
+
+    Ok(Json(SuggestionResponse {
+        interests: payload.interests,
+        suggestions,
+        explanation,
+        prompt,
+        timestamp: chrono::Utc::now(),
+    }))
 }
@@ -0,0 +1,115 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auth::{bearer_token, jwt, JwtKeys};
+use crate::database::Database;
+use crate::error::{AppError, ErrorCode};
+use crate::validation::ValidatedJson;
+use crate::models::{CreateUserRequest, LoginRequest, RefreshOutcome, RegisterRequest, TokenResponse, UserResponse};
+
+async fn issue_tokens(db: &Database, keys: &JwtKeys, user_id: Uuid) -> anyhow::Result<TokenResponse> {
+    let access_token = keys.issue_access_token(user_id)?;
+    let refresh_token = jwt::generate_refresh_token();
+    let expires_at = chrono::Utc::now() + keys.refresh_token_ttl;
+
+    db.create_refresh_token(user_id, &jwt::hash_refresh_token(&refresh_token), expires_at).await?;
+
+    Ok(TokenResponse {
+        access_token,
+        refresh_token,
+        token_type: "Bearer".to_string(),
+        expires_in: keys.access_token_ttl.num_seconds(),
+    })
+}
+
+pub async fn register(
+    State(db): State<Database>,
+    State(keys): State<JwtKeys>,
+    ValidatedJson(payload): ValidatedJson<RegisterRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let request = CreateUserRequest {
+        username: payload.username,
+        email: payload.email,
+        password: payload.password,
+        display_name: payload.display_name,
+        bio: None,
+        avatar_url: None,
+    };
+
+    let user = db.create_user(&request).await?;
+    let tokens = issue_tokens(&db, &keys, user.id).await?;
+    let user_response: UserResponse = user.into();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "注册成功", "user": user_response, "tokens": tokens }))
+    ))
+}
+
+pub async fn login(
+    State(db): State<Database>,
+    State(keys): State<JwtKeys>,
+    ValidatedJson(payload): ValidatedJson<LoginRequest>,
+) -> Result<Json<Value>, AppError> {
+    let user = db
+        .verify_credentials(&payload.username, &payload.password)
+        .await?
+        .ok_or(AppError::Coded(ErrorCode::InvalidCredentials))?;
+
+    let tokens = issue_tokens(&db, &keys, user.id).await?;
+    let user_response: UserResponse = user.into();
+
+    Ok(Json(json!({ "message": "登录成功", "user": user_response, "tokens": tokens })))
+}
+
+// 刷新令牌通过 Authorization: Bearer <refresh_token> 传入，每次使用后都会轮换
+pub async fn refresh(
+    State(db): State<Database>,
+    State(keys): State<JwtKeys>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, AppError> {
+    let presented = bearer_token(&headers)
+        .ok_or_else(|| AppError::Unauthorized("缺少刷新令牌".to_string()))?;
+
+    let refresh_token = jwt::generate_refresh_token();
+    let expires_at = chrono::Utc::now() + keys.refresh_token_ttl;
+
+    let outcome = db
+        .rotate_refresh_token(&jwt::hash_refresh_token(presented), &jwt::hash_refresh_token(&refresh_token), expires_at)
+        .await?;
+
+    let user_id = match outcome {
+        RefreshOutcome::Rotated { user_id } => user_id,
+        RefreshOutcome::Expired => return Err(AppError::Coded(ErrorCode::TokenExpired)),
+        RefreshOutcome::Invalid | RefreshOutcome::Reused => {
+            return Err(AppError::Coded(ErrorCode::TokenInvalid));
+        }
+    };
+
+    let tokens = TokenResponse {
+        access_token: keys.issue_access_token(user_id)?,
+        refresh_token,
+        token_type: "Bearer".to_string(),
+        expires_in: keys.access_token_ttl.num_seconds(),
+    };
+
+    Ok(Json(json!({ "message": "刷新成功", "tokens": tokens })))
+}
+
+pub async fn logout(
+    State(db): State<Database>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, AppError> {
+    let presented = bearer_token(&headers)
+        .ok_or_else(|| AppError::Unauthorized("缺少刷新令牌".to_string()))?;
+
+    // 无论令牌是否仍有效都返回成功，避免泄露令牌状态
+    db.revoke_refresh_token(&jwt::hash_refresh_token(presented)).await?;
+
+    Ok(Json(json!({ "message": "已退出登录" })))
+}
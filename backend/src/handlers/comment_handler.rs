@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auth::{authz, CurrentUser};
+use crate::models::{page_limit, parse_cursor, Comment, CommentListMode, CommentListQuery, CommentNode, CommentResponse, CreateCommentRequest, Cursor, Page, UpdateCommentRequest, MAX_COMMENT_DEPTH, MAX_TREE_REPLIES_PER_ROOT};
+use crate::database::Database;
+use crate::error::{AppError, ErrorCode};
+use crate::validation::{ValidatedJson, ValidatedQuery};
+
+async fn ensure_content_exists(db: &Database, content_id: Uuid) -> Result<(), AppError> {
+    db.get_content_by_id(content_id)
+        .await?
+        .ok_or(AppError::Coded(ErrorCode::ContentNotFound))?;
+    Ok(())
+}
+
+async fn find_comment(db: &Database, comment_id: Uuid) -> Result<Comment, AppError> {
+    db.get_comment_by_id(comment_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("评论不存在".to_string()))
+}
+
+fn comment_cursor(comment: &Comment) -> Cursor {
+    Cursor {
+        created_at: comment.created_at,
+        id: comment.id,
+        likes_count: None,
+    }
+}
+
+pub async fn get_comments(
+    State(db): State<Database>,
+    Path(content_id): Path<Uuid>,
+    ValidatedQuery(query): ValidatedQuery<CommentListQuery>,
+) -> Result<Response, AppError> {
+    ensure_content_exists(&db, content_id).await?;
+
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    match query.mode {
+        CommentListMode::Flat => {
+            let comments = db.get_comments_by_content_id(content_id, false, after.as_ref(), limit + 1).await?;
+            let page: Page<CommentResponse> = Page::from_rows(comments, limit, comment_cursor);
+            Ok(Json(page).into_response())
+        }
+        CommentListMode::Tree => {
+            // 按根评论分页，每个根评论附带最早的 MAX_TREE_REPLIES_PER_ROOT 条回复
+            let roots = db.get_comments_by_content_id(content_id, true, after.as_ref(), limit + 1).await?;
+            let page: Page<Comment> = Page::from_rows(roots, limit, comment_cursor);
+
+            let root_ids: Vec<Uuid> = page.items.iter().map(|comment| comment.id).collect();
+            let descendants = db.get_comment_descendants(&root_ids, MAX_TREE_REPLIES_PER_ROOT).await?;
+
+            Ok(Json(Page {
+                items: CommentNode::build_forest(page.items, descendants),
+                next_cursor: page.next_cursor,
+            })
+            .into_response())
+        }
+    }
+}
+
+async fn insert_comment(
+    db: &Database,
+    current_user: &CurrentUser,
+    content_id: Uuid,
+    payload: &CreateCommentRequest,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let parent = match payload.parent_id {
+        Some(parent_id) => {
+            let parent = find_comment(db, parent_id).await?;
+            if parent.content_id != content_id {
+                return Err(AppError::BadRequest("回复的评论不属于该内容".to_string()));
+            }
+            if parent.depth >= MAX_COMMENT_DEPTH {
+                return Err(AppError::BadRequest("回复层级过深".to_string()));
+            }
+            Some(parent)
+        }
+        None => None,
+    };
+
+    let comment = db.create_comment(content_id, payload, current_user.id, parent.as_ref()).await?;
+    let comment_response: CommentResponse = comment.into();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "评论发表成功", "comment": comment_response }))
+    ))
+}
+
+pub async fn create_comment(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(content_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CreateCommentRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    ensure_content_exists(&db, content_id).await?;
+
+    insert_comment(&db, &current_user, content_id, &payload).await
+}
+
+pub async fn get_replies(
+    State(db): State<Database>,
+    Path(comment_id): Path<Uuid>,
+    ValidatedQuery(query): ValidatedQuery<CommentListQuery>,
+) -> Result<Json<Page<CommentResponse>>, AppError> {
+    find_comment(&db, comment_id).await?;
+
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    let replies = db.get_replies(comment_id, after.as_ref(), limit + 1).await?;
+
+    Ok(Json(Page::from_rows(replies, limit, comment_cursor)))
+}
+
+pub async fn create_reply(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(comment_id): Path<Uuid>,
+    ValidatedJson(mut payload): ValidatedJson<CreateCommentRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let parent = find_comment(&db, comment_id).await?;
+    payload.parent_id = Some(parent.id);
+
+    insert_comment(&db, &current_user, parent.content_id, &payload).await
+}
+
+// 评论只能由作者本人编辑
+pub async fn update_comment(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(comment_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateCommentRequest>,
+) -> Result<Json<Value>, AppError> {
+    let comment = find_comment(&db, comment_id).await?;
+    if comment.author_id != current_user.id {
+        return Err(AppError::Forbidden("只能编辑自己的评论".to_string()));
+    }
+
+    let comment = db
+        .update_comment(comment_id, &payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("评论不存在".to_string()))?;
+    let comment_response: CommentResponse = comment.into();
+
+    Ok(Json(json!({ "message": "评论更新成功", "comment": comment_response })))
+}
+
+// 作者本人或版主、管理员可以删除评论
+pub async fn delete_comment(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(comment_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    let comment = find_comment(&db, comment_id).await?;
+    authz::ensure_can_modify(&current_user, comment.author_id, authz::CONTENT_ADMIN_ROLE, "无权限删除此评论")?;
+
+    if !db.delete_comment(comment_id).await? {
+        return Err(AppError::NotFound("评论不存在".to_string()));
+    }
+
+    Ok(Json(json!({ "message": "评论删除成功" })))
+}
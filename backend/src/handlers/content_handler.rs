@@ -6,112 +6,137 @@ use axum::{
 use serde_json::{json, Value};
 use uuid::Uuid;
 
-use crate::models::{Content, CreateContentRequest, ContentResponse};
+use crate::ai::ContentIndex;
+use crate::auth::{authz, CurrentUser};
+use crate::models::{page_limit, parse_cursor, ContentListQuery, ContentResponse, ContentSort, CreateContentRequest, Cursor, Page};
 use crate::database::Database;
+use crate::error::{AppError, ErrorCode};
+use crate::validation::{ValidatedJson, ValidatedQuery};
 
-pub async fn get_contents(State(db): State<Database>) -> Result<Json<Vec<ContentResponse>>, StatusCode> {
-    match db.get_all_contents().await {
-        Ok(contents) => {
-            let content_responses: Vec<ContentResponse> = contents
-                .into_iter()
-                .map(|content| content.into())
-                .collect();
-            Ok(Json(content_responses))
-        }
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+pub async fn get_contents(
+    State(db): State<Database>,
+    ValidatedQuery(query): ValidatedQuery<ContentListQuery>,
+) -> Result<Json<Page<ContentResponse>>, AppError> {
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    // 游标必须与排序方式匹配，按点赞数排序的游标需要携带点赞数
+    if query.sort == ContentSort::MostLiked && after.as_ref().is_some_and(|c| c.likes_count.is_none()) {
+        return Err(AppError::BadRequest("无效的分页游标".to_string()));
     }
+
+    let contents = db.list_contents(&query, after.as_ref(), limit + 1).await?;
+    let sort = query.sort;
+    let page = Page::from_rows(contents, limit, |content| Cursor {
+        created_at: content.created_at,
+        id: content.id,
+        likes_count: (sort == ContentSort::MostLiked).then_some(content.likes_count),
+    });
+
+    Ok(Json(page))
 }
 
 pub async fn create_content(
     State(db): State<Database>,
-    Json(payload): Json<CreateContentRequest>,
-) -> Result<(StatusCode, Json<Value>), StatusCode> {
-    // 这里假设作者 ID，实际项目中应从认证信息获取
-    let author_id = Uuid::new_v4(); // 临时 ID，实际应从 JWT 或 session 获取
-    
-    match db.create_content(&payload, author_id).await {
-        Ok(content) => {
-            let content_response: ContentResponse = content.into();
-            Ok((
-                StatusCode::CREATED,
-                Json(json!({ "message": "内容创建成功", "content": content_response }))
-            ))
-        }
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    State(index): State<ContentIndex>,
+    current_user: CurrentUser,
+    ValidatedJson(payload): ValidatedJson<CreateContentRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let content = db.create_content(&payload, current_user.id).await?;
+    index.spawn_index(content.id);
+    let content_response: ContentResponse = content.into();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "内容创建成功", "content": content_response }))
+    ))
 }
 
 pub async fn get_content_by_id(
     State(db): State<Database>,
     Path(content_id): Path<Uuid>,
-) -> Result<Json<ContentResponse>, StatusCode> {
-    match db.get_content_by_id(content_id).await {
-        Ok(Some(content)) => {
-            let content_response: ContentResponse = content.into();
-            Ok(Json(content_response))
-        }
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+) -> Result<Json<ContentResponse>, AppError> {
+    let content = db
+        .get_content_by_id(content_id)
+        .await?
+        .ok_or(AppError::Coded(ErrorCode::ContentNotFound))?;
+
+    Ok(Json(content.into()))
+}
+
+// 只有作者本人或版主、管理员可以修改和删除内容
+async fn authorize_content_owner(
+    db: &Database,
+    current_user: &CurrentUser,
+    content_id: Uuid,
+) -> Result<(), AppError> {
+    let content = db
+        .get_content_by_id(content_id)
+        .await?
+        .ok_or(AppError::Coded(ErrorCode::ContentNotFound))?;
+
+    if !current_user.can_modify(content.author_id, authz::CONTENT_ADMIN_ROLE) {
+        return Err(AppError::Coded(ErrorCode::ContentForbidden));
     }
+
+    Ok(())
 }
 
 pub async fn update_content(
     State(db): State<Database>,
+    State(index): State<ContentIndex>,
+    current_user: CurrentUser,
     Path(content_id): Path<Uuid>,
-    Json(payload): Json<CreateContentRequest>,
-) -> Result<Json<Value>, StatusCode> {
-    match db.update_content(content_id, &payload).await {
-        Ok(Some(content)) => {
-            let content_response: ContentResponse = content.into();
-            Ok(Json(json!({ "message": "内容更新成功", "content": content_response })))
-        }
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    ValidatedJson(payload): ValidatedJson<CreateContentRequest>,
+) -> Result<Json<Value>, AppError> {
+    authorize_content_owner(&db, &current_user, content_id).await?;
+
+    let content = db
+        .update_content(content_id, &payload)
+        .await?
+        .ok_or(AppError::Coded(ErrorCode::ContentNotFound))?;
+    index.spawn_index(content.id);
+    let content_response: ContentResponse = content.into();
+
+    Ok(Json(json!({ "message": "内容更新成功", "content": content_response })))
 }
 
 pub async fn delete_content(
     State(db): State<Database>,
+    State(index): State<ContentIndex>,
+    current_user: CurrentUser,
     Path(content_id): Path<Uuid>,
-) -> Result<Json<Value>, StatusCode> {
-    match db.delete_content(content_id).await {
-        Ok(true) => Ok(Json(json!({ "message": "内容删除成功" }))),
-        Ok(false) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+) -> Result<Json<Value>, AppError> {
+    authorize_content_owner(&db, &current_user, content_id).await?;
+
+    if !db.delete_content(content_id).await? {
+        return Err(AppError::Coded(ErrorCode::ContentNotFound));
     }
+    index.remove(content_id);
+
+    Ok(Json(json!({ "message": "内容删除成功" })))
 }
 
 pub async fn like_content(
     State(db): State<Database>,
+    current_user: CurrentUser,
     Path(content_id): Path<Uuid>,
-    Json(payload): Json<Value>,
-) -> Result<Json<Value>, StatusCode> {
-    // 这里假设用户 ID，实际项目中应从认证信息获取
-    let user_id = payload.get("user_id")
-        .and_then(|v| v.as_str())
-        .and_then(|s| Uuid::parse_str(s).ok())
-        .unwrap_or_else(|| Uuid::new_v4());
-    
-    match db.like_content(content_id, user_id).await {
-        Ok(true) => Ok(Json(json!({ "message": "点赞成功" }))),
-        Ok(false) => Ok(Json(json!({ "message": "已经点赞过了" }))),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+) -> Result<Json<Value>, AppError> {
+    if db.like_content(content_id, current_user.id).await? {
+        Ok(Json(json!({ "message": "点赞成功" })))
+    } else {
+        Ok(Json(json!({ "message": "已经点赞过了" })))
     }
 }
 
 pub async fn unlike_content(
     State(db): State<Database>,
+    current_user: CurrentUser,
     Path(content_id): Path<Uuid>,
-    Json(payload): Json<Value>,
-) -> Result<Json<Value>, StatusCode> {
-    let user_id = payload.get("user_id")
-        .and_then(|v| v.as_str())
-        .and_then(|s| Uuid::parse_str(s).ok())
-        .unwrap_or_else(|| Uuid::new_v4());
-    
-    match db.unlike_content(content_id, user_id).await {
-        Ok(true) => Ok(Json(json!({ "message": "取消点赞成功" }))),
-        Ok(false) => Ok(Json(json!({ "message": "尚未点赞" }))),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+) -> Result<Json<Value>, AppError> {
+    if db.unlike_content(content_id, current_user.id).await? {
+        Ok(Json(json!({ "message": "取消点赞成功" })))
+    } else {
+        Ok(Json(json!({ "message": "尚未点赞" })))
     }
 }
@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auth::CurrentUser;
+use crate::models::{page_limit, parse_cursor, AiConversation, ConversationDetail, ConversationListQuery, CreateConversationRequest, Cursor, Page};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::validation::{ValidatedJson, ValidatedQuery};
+
+// 会话只对创建者可见，其他人访问视为不存在
+async fn find_own_conversation(
+    db: &Database,
+    current_user: &CurrentUser,
+    conversation_id: Uuid,
+) -> Result<AiConversation, AppError> {
+    db.get_ai_conversation_by_id(conversation_id)
+        .await?
+        .filter(|conversation| conversation.user_id == current_user.id)
+        .ok_or_else(|| AppError::NotFound("会话不存在".to_string()))
+}
+
+pub async fn get_conversations(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedQuery(query): ValidatedQuery<ConversationListQuery>,
+) -> Result<Json<Page<AiConversation>>, AppError> {
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    let conversations = db
+        .list_ai_conversations(current_user.id, after.as_ref(), limit + 1)
+        .await?;
+    let page = Page::from_rows(conversations, limit, |conversation| Cursor {
+        created_at: conversation.created_at,
+        id: conversation.id,
+        likes_count: None,
+    });
+
+    Ok(Json(page))
+}
+
+pub async fn create_conversation(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedJson(payload): ValidatedJson<CreateConversationRequest>,
+) -> Result<(StatusCode, Json<AiConversation>), AppError> {
+    let title = payload.title.as_deref().map(str::trim).filter(|title| !title.is_empty());
+    let conversation = db.create_ai_conversation(current_user.id, title).await?;
+
+    Ok((StatusCode::CREATED, Json(conversation)))
+}
+
+pub async fn get_conversation(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(conversation_id): Path<Uuid>,
+) -> Result<Json<ConversationDetail>, AppError> {
+    let conversation = find_own_conversation(&db, &current_user, conversation_id).await?;
+    let messages = db.get_ai_messages(conversation.id).await?;
+
+    Ok(Json(ConversationDetail { conversation, messages }))
+}
+
+pub async fn delete_conversation(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(conversation_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    let conversation = find_own_conversation(&db, &current_user, conversation_id).await?;
+    db.delete_ai_conversation(conversation.id).await?;
+
+    Ok(Json(json!({ "message": "会话删除成功" })))
+}
@@ -0,0 +1,194 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auth::CurrentUser;
+use crate::models::{page_limit, parse_cursor, CreateFriendshipRequest, Cursor, Friendship, FriendshipListQuery, FriendshipResponse, FriendshipStatus, Page};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::validation::{ValidatedJson, ValidatedQuery};
+
+async fn find_friendship(db: &Database, friendship_id: Uuid) -> Result<Friendship, AppError> {
+    db.get_friendship_by_id(friendship_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("好友请求不存在".to_string()))
+}
+
+pub async fn get_friends(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedQuery(query): ValidatedQuery<FriendshipListQuery>,
+) -> Result<Json<Page<FriendshipResponse>>, AppError> {
+    let status = query.status.unwrap_or(FriendshipStatus::Accepted);
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    let friendships = db
+        .get_friendships_by_user_id(current_user.id, status, after.as_ref(), limit + 1)
+        .await?;
+    let page = Page::from_rows(friendships, limit, |friendship| Cursor {
+        created_at: friendship.created_at,
+        id: friendship.id,
+        likes_count: None,
+    });
+
+    Ok(Json(page))
+}
+
+pub async fn send_friend_request(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedJson(payload): ValidatedJson<CreateFriendshipRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    if payload.user_id == current_user.id {
+        return Err(AppError::BadRequest("不能添加自己为好友".to_string()));
+    }
+
+    db.get_user_by_id(payload.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+
+    // 同一对用户只保留一条关系记录，无论请求方向如何
+    let friendship = match db.get_friendship_between(current_user.id, payload.user_id).await? {
+        None => {
+            db.create_friendship(current_user.id, payload.user_id, payload.message.as_deref())
+                .await?
+        }
+        Some(existing) => match existing.status {
+            FriendshipStatus::Pending if existing.requested_by == current_user.id => {
+                return Err(AppError::Conflict("已经发送过好友请求".to_string()));
+            }
+            FriendshipStatus::Pending => {
+                return Err(AppError::Conflict("对方已向你发送好友请求，请直接处理该请求".to_string()));
+            }
+            FriendshipStatus::Accepted => {
+                return Err(AppError::Conflict("你们已经是好友了".to_string()));
+            }
+            // 不透露是哪一方设置了屏蔽
+            FriendshipStatus::Blocked => {
+                return Err(AppError::Forbidden("无法向该用户发送好友请求".to_string()));
+            }
+            FriendshipStatus::Declined => db
+                .reopen_friendship(existing.id, current_user.id, payload.message.as_deref())
+                .await?
+                .ok_or_else(|| AppError::Conflict("好友关系状态已变化，请重试".to_string()))?,
+        },
+    };
+
+    let friendship_response: FriendshipResponse = friendship.into();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "好友请求已发送", "friendship": friendship_response }))
+    ))
+}
+
+// 只有请求的接收方可以接受或拒绝
+async fn respond(
+    db: &Database,
+    current_user: &CurrentUser,
+    friendship_id: Uuid,
+    status: FriendshipStatus,
+) -> Result<Friendship, AppError> {
+    let friendship = find_friendship(db, friendship_id).await?;
+
+    let is_participant = friendship.user1_id == current_user.id || friendship.user2_id == current_user.id;
+    if !is_participant {
+        return Err(AppError::NotFound("好友请求不存在".to_string()));
+    }
+    if friendship.requested_by == current_user.id {
+        return Err(AppError::Forbidden("不能处理自己发出的好友请求".to_string()));
+    }
+    if friendship.status != FriendshipStatus::Pending {
+        return Err(AppError::Conflict("该好友请求已处理".to_string()));
+    }
+
+    db.respond_to_friendship(friendship_id, status)
+        .await?
+        .ok_or_else(|| AppError::Conflict("该好友请求已处理".to_string()))
+}
+
+pub async fn accept_friend_request(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(friendship_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    let friendship = respond(&db, &current_user, friendship_id, FriendshipStatus::Accepted).await?;
+    let friendship_response: FriendshipResponse = friendship.into();
+
+    Ok(Json(json!({ "message": "已接受好友请求", "friendship": friendship_response })))
+}
+
+pub async fn decline_friend_request(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(friendship_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    let friendship = respond(&db, &current_user, friendship_id, FriendshipStatus::Declined).await?;
+    let friendship_response: FriendshipResponse = friendship.into();
+
+    Ok(Json(json!({ "message": "已拒绝好友请求", "friendship": friendship_response })))
+}
+
+pub async fn block_user(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    if user_id == current_user.id {
+        return Err(AppError::BadRequest("不能屏蔽自己".to_string()));
+    }
+
+    if let Some(existing) = db.get_friendship_between(current_user.id, user_id).await?
+        && existing.status == FriendshipStatus::Blocked
+        && existing.blocked_by != Some(current_user.id)
+    {
+        return Err(AppError::Forbidden("无法屏蔽该用户".to_string()));
+    }
+
+    let friendship = db.block_user(current_user.id, user_id).await?;
+    let friendship_response: FriendshipResponse = friendship.into();
+
+    Ok(Json(json!({ "message": "已屏蔽该用户", "friendship": friendship_response })))
+}
+
+pub async fn unblock_user(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    let friendship = db
+        .get_friendship_between(current_user.id, user_id)
+        .await?
+        .filter(|f| f.status == FriendshipStatus::Blocked && f.blocked_by == Some(current_user.id))
+        .ok_or_else(|| AppError::NotFound("未屏蔽该用户".to_string()))?;
+
+    db.delete_friendship(friendship.id).await?;
+
+    Ok(Json(json!({ "message": "已取消屏蔽" })))
+}
+
+// 删除好友，也可用于撤回尚未处理的好友请求
+pub async fn remove_friend(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    let friendship = db
+        .get_friendship_between(current_user.id, user_id)
+        .await?
+        .filter(|f| matches!(f.status, FriendshipStatus::Accepted | FriendshipStatus::Pending))
+        .ok_or_else(|| AppError::NotFound("好友关系不存在".to_string()))?;
+
+    if friendship.status == FriendshipStatus::Pending && friendship.requested_by != current_user.id {
+        return Err(AppError::BadRequest("请使用拒绝接口处理收到的好友请求".to_string()));
+    }
+
+    db.delete_friendship(friendship.id).await?;
+
+    Ok(Json(json!({ "message": "已删除好友" })))
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde_json::{json, Value};
+
+use crate::database::Database;
+use crate::migrate::{self, MigrateMode, MigrationState};
+use crate::models::{CheckStatus, DatabaseCheck, MigrationCheck, ReadinessResponse};
+use crate::state::AppState;
+
+// 单项检查的超时时间，避免连接池耗尽时探针请求一直挂起
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+// 存活检查：进程能处理请求即可，不访问数据库
+pub async fn healthz() -> Json<Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn check_database(db: &Database) -> DatabaseCheck {
+    let started = Instant::now();
+    let status = match tokio::time::timeout(CHECK_TIMEOUT, db.ping()).await {
+        Ok(Ok(())) => CheckStatus::Ok,
+        Ok(Err(err)) => {
+            tracing::warn!("readiness database ping failed: {:?}", err);
+            CheckStatus::Error
+        }
+        Err(_) => {
+            tracing::warn!("readiness database ping timed out");
+            CheckStatus::Error
+        }
+    };
+
+    DatabaseCheck {
+        status,
+        latency_ms: (status == CheckStatus::Ok).then(|| started.elapsed().as_millis() as u64),
+        pool_size: db.pool().size(),
+        idle_connections: db.pool().num_idle(),
+    }
+}
+
+async fn check_migrations(db: &Database, mode: MigrateMode) -> MigrationCheck {
+    let mut check = MigrationCheck {
+        status: CheckStatus::Error,
+        current_version: None,
+        expected_version: migrate::expected_version(),
+        mismatched: Vec::new(),
+    };
+    if mode == MigrateMode::Skip {
+        check.status = CheckStatus::Skipped;
+        return check;
+    }
+
+    let statuses = match tokio::time::timeout(CHECK_TIMEOUT, migrate::status(db.pool())).await {
+        Ok(Ok(statuses)) => statuses,
+        Ok(Err(err)) => {
+            tracing::warn!("readiness migration check failed: {:?}", err);
+            return check;
+        }
+        Err(_) => {
+            tracing::warn!("readiness migration check timed out");
+            return check;
+        }
+    };
+
+    check.current_version = statuses
+        .iter()
+        .rfind(|status| status.state != MigrationState::Pending)
+        .map(|status| status.version);
+    check.mismatched = statuses
+        .iter()
+        .filter(|status| status.state != MigrationState::Applied)
+        .map(|status| status.version)
+        .collect();
+    if check.mismatched.is_empty() {
+        check.status = CheckStatus::Ok;
+    }
+    check
+}
+
+// 就绪检查：数据库可访问且数据库结构与本程序一致时返回 200，否则返回 503
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let database = check_database(&state.db).await;
+    let migrations = if database.status == CheckStatus::Ok {
+        check_migrations(&state.db, state.migrate).await
+    } else {
+        MigrationCheck {
+            status: CheckStatus::Error,
+            current_version: None,
+            expected_version: migrate::expected_version(),
+            mismatched: Vec::new(),
+        }
+    };
+
+    let ready = database.status == CheckStatus::Ok && migrations.status != CheckStatus::Error;
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(ReadinessResponse { ready, database, migrations }))
+}
@@ -1,7 +1,11 @@
+pub mod auth_handler;
 pub mod user_handler;
 pub mod content_handler;
+pub mod comment_handler;
+pub mod friendship_handler;
 pub mod ai_handler;
-
-pub use user_handler::*;
-pub use content_handler::*;
-pub use ai_handler::*;
+pub mod conversation_handler;
+pub mod study_plan_handler;
+pub mod quiz_handler;
+pub mod admin_handler;
+pub mod health_handler;
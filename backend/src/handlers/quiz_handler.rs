@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auth::CurrentUser;
+use crate::models::{page_limit, parse_cursor, Cursor, Page, Quiz, QuizResponse, QuizSubmission, QuizSubmissionListQuery, SubmitQuizRequest};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::validation::{ValidatedJson, ValidatedQuery};
+
+async fn find_quiz(db: &Database, quiz_id: Uuid) -> Result<Quiz, AppError> {
+    db.get_quiz_by_id(quiz_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("测验不存在".to_string()))
+}
+
+pub async fn get_quiz_by_id(
+    State(db): State<Database>,
+    Path(quiz_id): Path<Uuid>,
+) -> Result<Json<QuizResponse>, AppError> {
+    let quiz = find_quiz(&db, quiz_id).await?;
+
+    Ok(Json(quiz.into()))
+}
+
+// 提交答案后立即评分，并返回逐题结果和参考答案
+pub async fn submit_quiz(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(quiz_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<SubmitQuizRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    let quiz = find_quiz(&db, quiz_id).await?;
+
+    if payload
+        .answers
+        .iter()
+        .any(|answer| !quiz.questions.iter().any(|question| question.id == answer.question_id))
+    {
+        return Err(AppError::BadRequest("答案中包含不属于该测验的题目".to_string()));
+    }
+
+    let (results, score, max_score) = quiz.grade(&payload.answers);
+    let submission = db
+        .create_quiz_submission(quiz.id, current_user.id, &payload.answers, &results, score, max_score)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "答案已提交", "submission": submission }))
+    ))
+}
+
+pub async fn get_quiz_submissions(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(quiz_id): Path<Uuid>,
+    ValidatedQuery(query): ValidatedQuery<QuizSubmissionListQuery>,
+) -> Result<Json<Page<QuizSubmission>>, AppError> {
+    find_quiz(&db, quiz_id).await?;
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    let submissions = db
+        .list_quiz_submissions(quiz_id, current_user.id, after.as_ref(), limit + 1)
+        .await?;
+    let page = Page::from_rows(submissions, limit, |submission| Cursor {
+        created_at: submission.created_at,
+        id: submission.id,
+        likes_count: None,
+    });
+
+    Ok(Json(page))
+}
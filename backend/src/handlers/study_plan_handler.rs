@@ -0,0 +1,133 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auth::CurrentUser;
+use crate::models::{page_limit, parse_cursor, Cursor, Page, PlanDocument, SaveStudyPlanRequest, StudyPlan, StudyPlanListQuery, StudyPlanResponse, UpdatePlanTaskRequest, UpdateStudyPlanRequest};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::validation::{ValidatedJson, ValidatedQuery};
+
+// 学习计划只对创建者可见，其他人访问视为不存在
+async fn find_own_study_plan(
+    db: &Database,
+    current_user: &CurrentUser,
+    study_plan_id: Uuid,
+) -> Result<StudyPlan, AppError> {
+    db.get_study_plan_by_id(study_plan_id)
+        .await?
+        .filter(|study_plan| study_plan.user_id == current_user.id)
+        .ok_or_else(|| AppError::NotFound("学习计划不存在".to_string()))
+}
+
+// 用户提交的计划中关联的内容必须存在
+async fn ensure_contents_exist(db: &Database, plan: &PlanDocument) -> Result<(), AppError> {
+    let content_ids = plan.content_ids();
+    if content_ids.is_empty() {
+        return Ok(());
+    }
+
+    let existing = db.get_existing_content_ids(&content_ids).await?;
+    if existing.len() != content_ids.len() {
+        return Err(AppError::BadRequest("计划中关联的内容不存在".to_string()));
+    }
+    Ok(())
+}
+
+pub async fn get_study_plans(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedQuery(query): ValidatedQuery<StudyPlanListQuery>,
+) -> Result<Json<Page<StudyPlanResponse>>, AppError> {
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    let study_plans = db
+        .list_study_plans(current_user.id, after.as_ref(), limit + 1)
+        .await?;
+    let page = Page::from_rows(study_plans, limit, |study_plan| Cursor {
+        created_at: study_plan.created_at,
+        id: study_plan.id,
+        likes_count: None,
+    });
+
+    Ok(Json(page))
+}
+
+pub async fn create_study_plan(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    ValidatedJson(mut payload): ValidatedJson<SaveStudyPlanRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    ensure_contents_exist(&db, &payload.plan).await?;
+    payload.plan.ensure_unique_task_ids();
+
+    let study_plan = db.create_study_plan(current_user.id, &payload).await?;
+    let study_plan_response: StudyPlanResponse = study_plan.into();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "学习计划保存成功", "study_plan": study_plan_response }))
+    ))
+}
+
+pub async fn get_study_plan_by_id(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(study_plan_id): Path<Uuid>,
+) -> Result<Json<StudyPlanResponse>, AppError> {
+    let study_plan = find_own_study_plan(&db, &current_user, study_plan_id).await?;
+
+    Ok(Json(study_plan.into()))
+}
+
+pub async fn update_study_plan(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(study_plan_id): Path<Uuid>,
+    ValidatedJson(mut payload): ValidatedJson<UpdateStudyPlanRequest>,
+) -> Result<Json<Value>, AppError> {
+    find_own_study_plan(&db, &current_user, study_plan_id).await?;
+    ensure_contents_exist(&db, &payload.plan).await?;
+    payload.plan.ensure_unique_task_ids();
+
+    let study_plan = db
+        .update_study_plan(study_plan_id, &payload.plan)
+        .await?
+        .ok_or_else(|| AppError::NotFound("学习计划不存在".to_string()))?;
+    let study_plan_response: StudyPlanResponse = study_plan.into();
+
+    Ok(Json(json!({ "message": "学习计划更新成功", "study_plan": study_plan_response })))
+}
+
+pub async fn update_study_plan_task(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path((study_plan_id, task_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdatePlanTaskRequest>,
+) -> Result<Json<Value>, AppError> {
+    find_own_study_plan(&db, &current_user, study_plan_id).await?;
+
+    let study_plan = db
+        .set_study_plan_task_completed(study_plan_id, task_id, payload.completed)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+    let study_plan_response: StudyPlanResponse = study_plan.into();
+
+    Ok(Json(json!({ "message": "任务状态已更新", "study_plan": study_plan_response })))
+}
+
+pub async fn delete_study_plan(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(study_plan_id): Path<Uuid>,
+) -> Result<Json<Value>, AppError> {
+    find_own_study_plan(&db, &current_user, study_plan_id).await?;
+    db.delete_study_plan(study_plan_id).await?;
+
+    Ok(Json(json!({ "message": "学习计划删除成功" })))
+}
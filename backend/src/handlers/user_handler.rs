@@ -1,87 +1,125 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde_json::{json, Value};
 use uuid::Uuid;
 
-use crate::models::{User, CreateUserRequest, UserResponse, UpdateUserRequest};
+use crate::auth::{authz, CurrentUser};
+use crate::models::{page_limit, parse_cursor, CreateUserRequest, Cursor, Page, PublicUserResponse, User, UserListQuery, UserResponse, UpdateUserRequest, UpdateUserRoleRequest, UserRole};
 use crate::database::Database;
+use crate::error::AppError;
+use crate::validation::{ValidatedJson, ValidatedQuery};
 
-pub async fn get_users(State(db): State<Database>) -> Result<Json<Vec<UserResponse>>, StatusCode> {
-    match db.get_all_users().await {
-        Ok(users) => {
-            let user_responses: Vec<UserResponse> = users
-                .into_iter()
-                .map(|user| user.into())
-                .collect();
-            Ok(Json(user_responses))
-        }
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+// 邮箱属于个人信息，列表只对管理员返回完整资料，其他调用方拿到公开资料
+pub async fn get_users(
+    State(db): State<Database>,
+    current_user: Option<CurrentUser>,
+    ValidatedQuery(query): ValidatedQuery<UserListQuery>,
+) -> Result<Response, AppError> {
+    let limit = page_limit(query.limit);
+    let after = parse_cursor(query.cursor.as_deref())?;
+
+    let users = db.list_users(after.as_ref(), limit + 1).await?;
+    let cursor_of = |user: &User| Cursor {
+        created_at: user.created_at,
+        id: user.id,
+        likes_count: None,
+    };
+
+    let is_admin = current_user.is_some_and(|user| user.has_role(authz::USER_ADMIN_ROLE));
+    if is_admin {
+        let page: Page<UserResponse> = Page::from_rows(users, limit, cursor_of);
+        Ok(Json(page).into_response())
+    } else {
+        let page: Page<PublicUserResponse> = Page::from_rows(users, limit, cursor_of);
+        Ok(Json(page).into_response())
     }
 }
 
+// 管理员直接创建账号；普通用户通过 /api/auth/register 注册
 pub async fn create_user(
     State(db): State<Database>,
-    Json(payload): Json<CreateUserRequest>,
-) -> Result<(StatusCode, Json<Value>), StatusCode> {
-    // 检查用户名是否已存在
-    if let Ok(Some(_)) = db.get_user_by_username(&payload.username).await {
-        return Ok((
-            StatusCode::BAD_REQUEST,
-            Json(json!({ "message": "用户名已存在", "error": "用户名重复" }))
-        ));
-    }
+    current_user: CurrentUser,
+    ValidatedJson(payload): ValidatedJson<CreateUserRequest>,
+) -> Result<(StatusCode, Json<Value>), AppError> {
+    authz::ensure_role(&current_user, authz::USER_ADMIN_ROLE)?;
 
-    match db.create_user(&payload).await {
-        Ok(user) => {
-            let user_response: UserResponse = user.into();
-            Ok((
-                StatusCode::CREATED,
-                Json(json!({ "message": "用户创建成功", "user": user_response }))
-            ))
-        }
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    // 用户名、邮箱重复由数据库唯一约束保证，并映射为对应的错误码
+    let user = db.create_user(&payload).await?;
+    let user_response: UserResponse = user.into();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "用户创建成功", "user": user_response }))
+    ))
 }
 
 pub async fn get_user_by_id(
     State(db): State<Database>,
+    current_user: Option<CurrentUser>,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<UserResponse>, StatusCode> {
-    match db.get_user_by_id(user_id).await {
-        Ok(Some(user)) => {
-            let user_response: UserResponse = user.into();
-            Ok(Json(user_response))
-        }
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+) -> Result<Response, AppError> {
+    let user = db
+        .get_user_by_id(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+
+    // 本人或管理员可以看到邮箱
+    let is_self_or_admin = current_user.is_some_and(|current| current.can_modify(user.id, authz::USER_ADMIN_ROLE));
+    if is_self_or_admin {
+        Ok(Json(UserResponse::from(user)).into_response())
+    } else {
+        Ok(Json(PublicUserResponse::from(user)).into_response())
     }
 }
 
 pub async fn update_user(
     State(db): State<Database>,
+    current_user: CurrentUser,
     Path(user_id): Path<Uuid>,
-    Json(payload): Json<UpdateUserRequest>,
-) -> Result<Json<Value>, StatusCode> {
-    match db.update_user(user_id, &payload).await {
-        Ok(Some(user)) => {
-            let user_response: UserResponse = user.into();
-            Ok(Json(json!({ "message": "用户信息更新成功", "user": user_response })))
-        }
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    ValidatedJson(payload): ValidatedJson<UpdateUserRequest>,
+) -> Result<Json<Value>, AppError> {
+    authz::ensure_can_modify(&current_user, user_id, authz::USER_ADMIN_ROLE, "无权限修改此用户")?;
+
+    let user = db
+        .update_user(user_id, &payload)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+    let user_response: UserResponse = user.into();
+
+    Ok(Json(json!({ "message": "用户信息更新成功", "user": user_response })))
+}
+
+pub async fn update_user_role(
+    State(db): State<Database>,
+    current_user: CurrentUser,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateUserRoleRequest>,
+) -> Result<Json<Value>, AppError> {
+    authz::ensure_role(&current_user, UserRole::Admin)?;
+
+    let user = db
+        .update_user_role(user_id, payload.role)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+    let user_response: UserResponse = user.into();
+
+    Ok(Json(json!({ "message": "用户角色更新成功", "user": user_response })))
 }
 
 pub async fn delete_user(
     State(db): State<Database>,
+    current_user: CurrentUser,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<Value>, StatusCode> {
-    match db.delete_user(user_id).await {
-        Ok(true) => Ok(Json(json!({ "message": "用户删除成功" }))),
-        Ok(false) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+) -> Result<Json<Value>, AppError> {
+    authz::ensure_can_modify(&current_user, user_id, authz::USER_ADMIN_ROLE, "无权限删除此用户")?;
+
+    if !db.delete_user(user_id).await? {
+        return Err(AppError::NotFound("用户不存在".to_string()));
     }
+
+    Ok(Json(json!({ "message": "用户删除成功" })))
 }
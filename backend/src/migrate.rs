@@ -0,0 +1,194 @@
+use std::{collections::HashMap, str::FromStr};
+
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+use sqlx::PgPool;
+
+// migrations 目录在编译时嵌入二进制文件，新增迁移后需要重新编译（见 build.rs）
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+const USAGE: &str = "usage: backend migrate <up|status|revert>";
+
+// 启动时如何处理未执行的迁移
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrateMode {
+    // 自动执行未执行的迁移（默认）
+    Apply,
+    // 只检查，数据库结构与程序不一致时拒绝启动，适合由发布流程单独执行迁移的环境
+    Check,
+    Skip,
+}
+
+impl FromStr for MigrateMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "apply" => Ok(MigrateMode::Apply),
+            "check" => Ok(MigrateMode::Check),
+            "skip" => Ok(MigrateMode::Skip),
+            other => Err(format!("expected apply, check or skip, got `{}`", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    // 已执行，但迁移文件在执行后被修改过
+    Modified,
+    // 数据库中有记录，但当前程序中没有这个迁移（通常是用旧版本程序连接了新数据库）
+    Missing,
+}
+
+impl MigrationState {
+    fn label(self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Modified => "modified",
+            MigrationState::Missing => "missing",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+// 只读取迁移记录，不会创建 _sqlx_migrations 表
+pub async fn status(pool: &PgPool) -> anyhow::Result<Vec<MigrationStatus>> {
+    let mut conn = pool.acquire().await?;
+    let initialized: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let mut applied = HashMap::new();
+    if initialized {
+        if let Some(version) = conn.dirty_version().await? {
+            return Err(MigrateError::Dirty(version).into());
+        }
+        for migration in conn.list_applied_migrations().await? {
+            applied.insert(migration.version, migration.checksum);
+        }
+    }
+
+    let mut statuses: Vec<MigrationStatus> = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            state: match applied.get(&migration.version) {
+                None => MigrationState::Pending,
+                Some(checksum) if *checksum == migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::Modified,
+            },
+        })
+        .collect();
+    statuses.extend(
+        applied
+            .keys()
+            .filter(|version| !MIGRATOR.version_exists(**version))
+            .map(|version| MigrationStatus {
+                version: *version,
+                description: String::new(),
+                state: MigrationState::Missing,
+            }),
+    );
+    statuses.sort_by_key(|status| status.version);
+
+    Ok(statuses)
+}
+
+// 本程序包含的最新迁移版本
+pub fn expected_version() -> Option<i64> {
+    MIGRATOR.iter().map(|migration| migration.version).max()
+}
+
+fn latest_applied(statuses: &[MigrationStatus]) -> Option<&MigrationStatus> {
+    statuses.iter().rfind(|status| status.state != MigrationState::Pending)
+}
+
+pub async fn on_startup(pool: &PgPool, mode: MigrateMode) -> anyhow::Result<()> {
+    match mode {
+        MigrateMode::Apply => {
+            MIGRATOR.run(pool).await?;
+        }
+        MigrateMode::Check => {
+            let statuses = status(pool).await?;
+            let mismatched: Vec<String> = statuses
+                .iter()
+                .filter(|status| status.state != MigrationState::Applied)
+                .map(|status| format!("{:03} ({})", status.version, status.state.label()))
+                .collect();
+            if !mismatched.is_empty() {
+                anyhow::bail!(
+                    "database schema does not match this build: {}; run `backend migrate status` for details",
+                    mismatched.join(", ")
+                );
+            }
+        }
+        MigrateMode::Skip => {
+            tracing::warn!("DATABASE_MIGRATE=skip, database schema is not checked");
+            return Ok(());
+        }
+    }
+
+    if let Some(latest) = latest_applied(&status(pool).await?) {
+        tracing::info!("🗂️ 数据库结构版本: {:03} {}", latest.version, latest.description);
+    }
+    Ok(())
+}
+
+// backend migrate up|status|revert
+pub async fn run_command(pool: &PgPool, args: &[String]) -> anyhow::Result<()> {
+    match args {
+        [command] if command == "up" => {
+            let pending: Vec<MigrationStatus> = status(pool)
+                .await?
+                .into_iter()
+                .filter(|status| status.state == MigrationState::Pending)
+                .collect();
+            MIGRATOR.run(pool).await?;
+
+            if pending.is_empty() {
+                println!("database is up to date");
+            }
+            for migration in pending {
+                println!("applied {:03} {}", migration.version, migration.description);
+            }
+        }
+        [command] if command == "status" => {
+            for migration in status(pool).await? {
+                println!(
+                    "{:03}  {:<8}  {}",
+                    migration.version,
+                    migration.state.label(),
+                    migration.description
+                );
+            }
+        }
+        // 每次只回退最近执行的一个迁移
+        [command] if command == "revert" => {
+            let statuses = status(pool).await?;
+            let Some(latest) = latest_applied(&statuses) else {
+                println!("no migrations to revert");
+                return Ok(());
+            };
+            let target = statuses
+                .iter()
+                .rfind(|status| status.state != MigrationState::Pending && status.version < latest.version)
+                .map_or(0, |status| status.version);
+
+            MIGRATOR.undo(pool, target).await?;
+            println!("reverted {:03} {}", latest.version, latest.description);
+        }
+        _ => anyhow::bail!(USAGE),
+    }
+
+    Ok(())
+}
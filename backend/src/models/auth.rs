@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use crate::validation::USERNAME_RE;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RefreshToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub replaced_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RegisterRequest {
+    #[validate(
+        length(min = 3, max = 32, message = "用户名长度必须在 3 到 32 个字符之间"),
+        regex(path = *USERNAME_RE, message = "用户名只能包含字母、数字、下划线和连字符")
+    )]
+    pub username: String,
+    #[validate(email(message = "邮箱格式不正确"), length(max = 255, message = "邮箱长度不能超过 255 个字符"))]
+    pub email: String,
+    #[validate(length(min = 8, max = 128, message = "密码长度必须在 8 到 128 个字符之间"))]
+    pub password: String,
+    #[validate(must_match(other = "password", message = "两次输入的密码不一致"))]
+    pub confirm_password: String,
+    #[validate(length(max = 255, message = "显示名称长度不能超过 255 个字符"))]
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct LoginRequest {
+    #[validate(length(min = 1, max = 32, message = "用户名不能为空"))]
+    pub username: String,
+    #[validate(length(min = 1, max = 128, message = "密码不能为空"))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+}
+
+// 刷新令牌轮换的结果
+#[derive(Debug)]
+pub enum RefreshOutcome {
+    Rotated { user_id: Uuid },
+    Invalid,
+    Expired,
+    // 已被轮换过的旧令牌再次出现，视为泄露，该用户的全部刷新令牌均已吊销
+    Reused,
+}
@@ -1,28 +1,72 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use validator::Validate;
+
+// 根评论深度为 0，回复最多嵌套到该深度
+pub const MAX_COMMENT_DEPTH: i32 = 4;
+// 树形列表中每个根评论最多附带的回复数，更多回复通过 /replies 分页获取
+pub const MAX_TREE_REPLIES_PER_ROOT: i64 = 200;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Comment {
     pub id: Uuid,
     pub content_id: Uuid,
     pub author_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub depth: i32,
+    pub reply_count: i32,
     pub content: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateCommentRequest {
-    pub content_id: Uuid,
+    #[validate(
+        length(min = 1, max = 2000, message = "评论长度必须在 1 到 2000 个字符之间"),
+        custom(function = "crate::validation::not_blank")
+    )]
+    pub content: String,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateCommentRequest {
+    #[validate(
+        length(min = 1, max = 2000, message = "评论长度必须在 1 到 2000 个字符之间"),
+        custom(function = "crate::validation::not_blank")
+    )]
     pub content: String,
 }
 
+// flat：按时间顺序返回全部评论；tree：分页返回根评论，并附带最早的若干条回复组成的回复树
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentListMode {
+    #[default]
+    Flat,
+    Tree,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CommentListQuery {
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在 1 到 100 之间"))]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub mode: CommentListMode,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CommentResponse {
     pub id: Uuid,
     pub content_id: Uuid,
     pub author_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub depth: i32,
+    pub reply_count: i32,
     pub content: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -34,9 +78,120 @@ impl From<Comment> for CommentResponse {
             id: comment.id,
             content_id: comment.content_id,
             author_id: comment.author_id,
+            parent_id: comment.parent_id,
+            depth: comment.depth,
+            reply_count: comment.reply_count,
             content: comment.content,
             created_at: comment.created_at,
             updated_at: comment.updated_at,
         }
     }
 }
+
+#[derive(Debug, Serialize)]
+pub struct CommentNode {
+    #[serde(flatten)]
+    pub comment: CommentResponse,
+    pub replies: Vec<CommentNode>,
+}
+
+impl CommentNode {
+    // 把根评论和它们的全部后代组装成树，descendants 需按时间升序排列
+    pub fn build_forest(roots: Vec<Comment>, descendants: Vec<Comment>) -> Vec<CommentNode> {
+        let mut children: HashMap<Uuid, Vec<Comment>> = HashMap::new();
+        for comment in descendants {
+            if let Some(parent_id) = comment.parent_id {
+                children.entry(parent_id).or_default().push(comment);
+            }
+        }
+
+        roots
+            .into_iter()
+            .map(|root| Self::build(root, &mut children))
+            .collect()
+    }
+
+    fn build(comment: Comment, children: &mut HashMap<Uuid, Vec<Comment>>) -> CommentNode {
+        let replies = children
+            .remove(&comment.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| Self::build(child, children))
+            .collect();
+
+        CommentNode {
+            comment: comment.into(),
+            replies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: u128, parent_id: Option<u128>, minute: i64) -> Comment {
+        let created_at = DateTime::from_timestamp(1_700_000_000 + minute * 60, 0).unwrap();
+        Comment {
+            id: Uuid::from_u128(id),
+            content_id: Uuid::nil(),
+            author_id: Uuid::nil(),
+            parent_id: parent_id.map(Uuid::from_u128),
+            depth: 0,
+            reply_count: 0,
+            content: format!("comment {id}"),
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    fn ids(nodes: &[CommentNode]) -> Vec<Uuid> {
+        nodes.iter().map(|node| node.comment.id).collect()
+    }
+
+    #[test]
+    fn builds_nested_replies_in_time_order() {
+        let roots = vec![comment(1, None, 0), comment(2, None, 1)];
+        let descendants = vec![
+            comment(10, Some(1), 2),
+            comment(11, Some(1), 3),
+            comment(20, Some(2), 4),
+            comment(100, Some(10), 5),
+            comment(101, Some(10), 6),
+        ];
+
+        let forest = CommentNode::build_forest(roots, descendants);
+
+        assert_eq!(ids(&forest), vec![Uuid::from_u128(1), Uuid::from_u128(2)]);
+        assert_eq!(ids(&forest[0].replies), vec![Uuid::from_u128(10), Uuid::from_u128(11)]);
+        assert_eq!(ids(&forest[0].replies[0].replies), vec![Uuid::from_u128(100), Uuid::from_u128(101)]);
+        assert!(forest[0].replies[1].replies.is_empty());
+        assert_eq!(ids(&forest[1].replies), vec![Uuid::from_u128(20)]);
+    }
+
+    #[test]
+    fn orphans_are_dropped() {
+        let roots = vec![comment(1, None, 0)];
+        let descendants = vec![
+            comment(10, Some(1), 1),
+            // 父评论不在本页（例如超出每个根评论的回复上限）
+            comment(30, Some(99), 2),
+            comment(31, Some(30), 3),
+        ];
+
+        let forest = CommentNode::build_forest(roots, descendants);
+
+        assert_eq!(forest.len(), 1);
+        assert_eq!(ids(&forest[0].replies), vec![Uuid::from_u128(10)]);
+        assert!(forest[0].replies[0].replies.is_empty());
+    }
+
+    #[test]
+    fn roots_keep_page_order() {
+        let roots = vec![comment(2, None, 1), comment(1, None, 0)];
+        let forest = CommentNode::build_forest(roots, Vec::new());
+
+        assert_eq!(ids(&forest), vec![Uuid::from_u128(2), Uuid::from_u128(1)]);
+        assert!(forest.iter().all(|node| node.replies.is_empty()));
+    }
+}
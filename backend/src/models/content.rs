@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use validator::Validate;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Content {
@@ -15,14 +16,55 @@ pub struct Content {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// 内容正文切分后的段落及其向量，title 来自所属内容
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ContentChunk {
+    pub content_id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateContentRequest {
+    #[validate(
+        length(min = 1, max = 500, message = "标题长度必须在 1 到 500 个字符之间"),
+        custom(function = "crate::validation::not_blank")
+    )]
     pub title: String,
+    #[validate(
+        length(min = 1, max = 100000, message = "正文长度必须在 1 到 100000 个字符之间"),
+        custom(function = "crate::validation::not_blank")
+    )]
     pub body: String,
+    #[validate(custom(function = "crate::validation::content_type"))]
     pub content_type: String,
+    #[validate(custom(function = "crate::validation::tags"))]
     pub tags: Vec<String>,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentSort {
+    #[default]
+    Newest,
+    MostLiked,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ContentListQuery {
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在 1 到 100 之间"))]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub sort: ContentSort,
+    #[validate(custom(function = "crate::validation::content_type"))]
+    pub content_type: Option<String>,
+    #[validate(length(min = 1, max = 30, message = "标签长度必须在 1 到 30 个字符之间"))]
+    pub tag: Option<String>,
+    pub author_id: Option<Uuid>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContentResponse {
     pub id: Uuid,
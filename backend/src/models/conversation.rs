@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use crate::ai::{ChatMessage, ChatRole};
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AiConversation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub title: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AiMessage {
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    pub role: ChatRole,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AiMessage> for ChatMessage {
+    fn from(message: AiMessage) -> Self {
+        ChatMessage {
+            role: message.role,
+            content: message.content,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateConversationRequest {
+    #[validate(length(max = 200, message = "标题长度不能超过 200 个字符"))]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConversationListQuery {
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在 1 到 100 之间"))]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConversationDetail {
+    #[serde(flatten)]
+    pub conversation: AiConversation,
+    pub messages: Vec<AiMessage>,
+}
@@ -1,12 +1,15 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use validator::Validate;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum FriendshipStatus {
     Pending,
     Accepted,
+    Declined,
     Blocked,
 }
 
@@ -16,13 +19,27 @@ pub struct Friendship {
     pub user1_id: Uuid,
     pub user2_id: Uuid,
     pub status: FriendshipStatus,
+    pub requested_by: Uuid,
+    pub request_message: Option<String>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub blocked_by: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateFriendshipRequest {
-    pub user2_id: Uuid,
+    pub user_id: Uuid,
+    #[validate(length(max = 500, message = "请求消息长度不能超过 500 个字符"))]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct FriendshipListQuery {
+    pub status: Option<FriendshipStatus>,
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在 1 到 100 之间"))]
+    pub limit: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,6 +48,9 @@ pub struct FriendshipResponse {
     pub user1_id: Uuid,
     pub user2_id: Uuid,
     pub status: FriendshipStatus,
+    pub requested_by: Uuid,
+    pub request_message: Option<String>,
+    pub responded_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -42,6 +62,9 @@ impl From<Friendship> for FriendshipResponse {
             user1_id: friendship.user1_id,
             user2_id: friendship.user2_id,
             status: friendship.status,
+            requested_by: friendship.requested_by,
+            request_message: friendship.request_message,
+            responded_at: friendship.responded_at,
             created_at: friendship.created_at,
             updated_at: friendship.updated_at,
         }
@@ -0,0 +1,36 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Error,
+    // DATABASE_MIGRATE=skip 时不检查数据库结构
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseCheck {
+    pub status: CheckStatus,
+    pub latency_ms: Option<u64>,
+    // 连接池状态，便于观察连接是否耗尽
+    pub pool_size: u32,
+    pub idle_connections: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrationCheck {
+    pub status: CheckStatus,
+    // 数据库中最近执行的迁移版本，以及本程序包含的最新迁移版本
+    pub current_version: Option<i64>,
+    pub expected_version: Option<i64>,
+    // 未执行、已被修改或程序中不存在的迁移版本
+    pub mismatched: Vec<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    pub database: DatabaseCheck,
+    pub migrations: MigrationCheck,
+}
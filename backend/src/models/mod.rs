@@ -1,9 +1,29 @@
+pub mod auth;
 pub mod user;
 pub mod content;
 pub mod comment;
 pub mod friendship;
+pub mod conversation;
+pub mod study_plan;
+pub mod suggestion;
+pub mod quiz;
+pub mod usage;
+pub mod prompt;
+pub mod moderation;
+pub mod health;
+pub mod pagination;
 
-pub use user::{User, CreateUserRequest, UserResponse, UpdateUserRequest};
-pub use content::{Content, CreateContentRequest, ContentResponse};
-pub use comment::{Comment, CreateCommentRequest, CommentResponse};
-pub use friendship::{Friendship, CreateFriendshipRequest, FriendshipResponse, FriendshipStatus};
+pub use auth::{RefreshToken, RegisterRequest, LoginRequest, TokenResponse, RefreshOutcome};
+pub use user::{User, UserRole, CreateUserRequest, UserResponse, PublicUserResponse, UpdateUserRequest, UpdateUserRoleRequest, UserListQuery};
+pub use content::{Content, ContentChunk, CreateContentRequest, ContentResponse, ContentListQuery, ContentSort};
+pub use comment::{Comment, CreateCommentRequest, UpdateCommentRequest, CommentListMode, CommentListQuery, CommentNode, CommentResponse, MAX_COMMENT_DEPTH, MAX_TREE_REPLIES_PER_ROOT};
+pub use friendship::{Friendship, CreateFriendshipRequest, FriendshipListQuery, FriendshipResponse, FriendshipStatus};
+pub use conversation::{AiConversation, AiMessage, CreateConversationRequest, ConversationListQuery, ConversationDetail};
+pub use study_plan::{PlanDocument, PLAN_DOCUMENT_SCHEMA, StudyPlan, StudyPlanRequest, SaveStudyPlanRequest, UpdateStudyPlanRequest, UpdatePlanTaskRequest, StudyPlanListQuery, StudyPlanResponse};
+pub use suggestion::{SuggestionRequest, SuggestionCandidate, Suggestion, SuggestionResponse};
+pub use quiz::{QuestionResult, Quiz, QuizDraft, QuizRequest, QuizResponse, QuizAnswer, SubmitQuizRequest, QuizSubmission, QuizSubmissionListQuery, QUIZ_DRAFT_SCHEMA};
+pub use usage::{AiUsageQuery, AiUsageReport, EndpointUsage, PromptUsage, UsageWindow, UserUsage};
+pub use prompt::{PromptTemplate, PromptVersion, CreatePromptTemplateRequest, UpdatePromptTemplateRequest, PromptTemplateListQuery, PromptOverview};
+pub use moderation::{ModerationRule, ModerationRuleKind, ModerationAction, ModerationStage, ModerationSource, CreateModerationRuleRequest, UpdateModerationRuleRequest, ModerationIncident, NewModerationIncident, ModerationIncidentListQuery, ModerationVerdict, MODERATION_VERDICT_SCHEMA};
+pub use health::{CheckStatus, DatabaseCheck, MigrationCheck, ReadinessResponse};
+pub use pagination::{Cursor, Page, page_limit, parse_cursor};
@@ -0,0 +1,128 @@
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use validator::Validate;
+
+pub static MODERATION_VERDICT_SCHEMA: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::to_value(schemars::schema_for!(ModerationVerdict)).expect("moderation schema is serializable")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationRuleKind {
+    // 按字面匹配，不区分大小写
+    Keyword,
+    Regex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationAction {
+    // 拒绝整条消息
+    Block,
+    // 把命中的片段替换为 ***
+    Redact,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationStage {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationSource {
+    Rule,
+    Classifier,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ModerationRule {
+    pub id: Uuid,
+    pub kind: ModerationRuleKind,
+    pub pattern: String,
+    pub action: ModerationAction,
+    pub category: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateModerationRuleRequest {
+    pub kind: ModerationRuleKind,
+    #[validate(length(min = 1, max = 500, message = "规则内容长度必须在 1 到 500 个字符之间"))]
+    pub pattern: String,
+    pub action: ModerationAction,
+    #[validate(length(min = 1, max = 50, message = "分类长度必须在 1 到 50 个字符之间"))]
+    pub category: String,
+    #[validate(length(max = 500, message = "说明不能超过 500 个字符"))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateModerationRuleRequest {
+    pub action: Option<ModerationAction>,
+    #[validate(length(min = 1, max = 50, message = "分类长度必须在 1 到 50 个字符之间"))]
+    pub category: Option<String>,
+    #[validate(length(max = 500, message = "说明不能超过 500 个字符"))]
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ModerationIncident {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub feature: String,
+    pub stage: ModerationStage,
+    pub action: ModerationAction,
+    pub source: ModerationSource,
+    pub rule_id: Option<Uuid>,
+    pub category: String,
+    // 命中的片段或分类器给出的理由，已截断
+    pub excerpt: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewModerationIncident {
+    pub user_id: Option<Uuid>,
+    pub feature: &'static str,
+    pub stage: ModerationStage,
+    pub action: ModerationAction,
+    pub source: ModerationSource,
+    pub rule_id: Option<Uuid>,
+    pub category: String,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ModerationIncidentListQuery {
+    pub stage: Option<ModerationStage>,
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在 1 到 100 之间"))]
+    pub limit: Option<i64>,
+}
+
+// 安全分类器的输出
+#[derive(Debug, Clone, Deserialize, JsonSchema, Validate)]
+pub struct ModerationVerdict {
+    // 是否包含不适合全年龄段学习平台的内容
+    pub flagged: bool,
+    #[validate(length(max = 50))]
+    pub category: String,
+    #[validate(length(max = 500))]
+    pub reason: String,
+}
@@ -0,0 +1,137 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+// 分页响应信封，next_cursor 为空表示没有下一页
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+// 键集分页游标：记录上一页最后一条记录的排序键，对客户端是不透明的 base64 字符串
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cursor {
+    #[serde(rename = "t")]
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+    #[serde(rename = "l", default, skip_serializing_if = "Option::is_none")]
+    pub likes_count: Option<i32>,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("cursor serialization cannot fail");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(value).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+impl<T> Page<T> {
+    // rows 需要比 limit 多取一条，用来判断是否还有下一页
+    pub fn from_rows<R>(mut rows: Vec<R>, limit: i64, cursor_of: impl Fn(&R) -> Cursor) -> Self
+    where
+        R: Into<T>,
+    {
+        let has_more = rows.len() as i64 > limit;
+        rows.truncate(limit as usize);
+
+        let next_cursor = if has_more {
+            rows.last().map(|row| cursor_of(row).encode())
+        } else {
+            None
+        };
+
+        Page {
+            items: rows.into_iter().map(Into::into).collect(),
+            next_cursor,
+        }
+    }
+}
+
+pub fn page_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+pub fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, AppError> {
+    cursor
+        .map(|value| Cursor::decode(value).ok_or_else(|| AppError::BadRequest("无效的分页游标".to_string())))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(id: u128, likes_count: Option<i32>) -> Cursor {
+        Cursor {
+            created_at: DateTime::from_timestamp(1_700_000_000 + id as i64, 0).unwrap(),
+            id: Uuid::from_u128(id),
+            likes_count,
+        }
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        for original in [cursor(1, None), cursor(2, Some(42))] {
+            let decoded = Cursor::decode(&original.encode()).unwrap();
+            assert_eq!(decoded.created_at, original.created_at);
+            assert_eq!(decoded.id, original.id);
+            assert_eq!(decoded.likes_count, original.likes_count);
+        }
+    }
+
+    #[test]
+    fn malformed_or_tampered_cursor_is_rejected() {
+        let valid = cursor(1, None).encode();
+        let truncated = &valid[..valid.len() / 2];
+        let tampered = URL_SAFE_NO_PAD.encode(br#"{"t":"yesterday","id":"not-a-uuid"}"#);
+
+        for value in ["", "not base64!", truncated, tampered.as_str()] {
+            assert!(
+                matches!(parse_cursor(Some(value)), Err(AppError::BadRequest(_))),
+                "cursor {value:?} should be rejected"
+            );
+        }
+        assert!(parse_cursor(None).unwrap().is_none());
+        assert!(parse_cursor(Some(&valid)).unwrap().is_some());
+    }
+
+    #[test]
+    fn extra_row_becomes_next_cursor() {
+        let rows: Vec<u128> = (1..=4).collect();
+        let page: Page<u128> = Page::from_rows(rows, 3, |&id| cursor(id, None));
+
+        assert_eq!(page.items, vec![1, 2, 3]);
+        let next = Cursor::decode(page.next_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!(next.id, Uuid::from_u128(3));
+    }
+
+    #[test]
+    fn last_page_has_no_next_cursor() {
+        let page: Page<u128> = Page::from_rows(vec![1, 2, 3], 3, |&id| cursor(id, None));
+        assert_eq!(page.items.len(), 3);
+        assert!(page.next_cursor.is_none());
+
+        let empty: Page<u128> = Page::from_rows(Vec::<u128>::new(), 3, |&id| cursor(id, None));
+        assert!(empty.items.is_empty());
+        assert!(empty.next_cursor.is_none());
+    }
+
+    #[test]
+    fn page_limit_is_clamped() {
+        assert_eq!(page_limit(None), DEFAULT_PAGE_SIZE);
+        assert_eq!(page_limit(Some(0)), 1);
+        assert_eq!(page_limit(Some(1000)), MAX_PAGE_SIZE);
+    }
+}
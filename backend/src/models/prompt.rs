@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PromptTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub version: i32,
+    pub template: String,
+    pub description: Option<String>,
+    pub weight: i32,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 生成某次 AI 回复所用的提示词版本，version 为 0 表示内置模板
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptVersion {
+    pub name: String,
+    pub version: i32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreatePromptTemplateRequest {
+    #[validate(length(min = 1, max = 100, message = "模板名称不能为空"))]
+    pub name: String,
+    #[validate(length(min = 1, max = 20000, message = "模板内容长度必须在 1 到 20000 个字符之间"))]
+    pub template: String,
+    #[validate(length(max = 500, message = "说明不能超过 500 个字符"))]
+    pub description: Option<String>,
+    // 流量权重，0 表示暂不启用
+    #[validate(range(min = 0, max = 100, message = "权重必须在 0 到 100 之间"))]
+    pub weight: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdatePromptTemplateRequest {
+    #[validate(range(min = 0, max = 100, message = "权重必须在 0 到 100 之间"))]
+    pub weight: i32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct PromptTemplateListQuery {
+    #[validate(length(min = 1, max = 100, message = "模板名称不能为空"))]
+    pub name: Option<String>,
+}
+
+// 可配置的提示词及其变量、内置模板和已保存的各个版本
+#[derive(Debug, Serialize)]
+pub struct PromptOverview {
+    pub name: &'static str,
+    pub variables: &'static [&'static str],
+    pub builtin: &'static str,
+    pub versions: Vec<PromptTemplate>,
+}
@@ -0,0 +1,413 @@
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use sqlx::types::Json;
+use validator::{Validate, ValidationError};
+
+use super::PromptVersion;
+use crate::ai::prompts::QUIZ_PROMPT;
+
+pub static QUIZ_DRAFT_SCHEMA: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::to_value(schemars::schema_for!(QuizDraft)).expect("quiz schema is serializable")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    MultipleChoice,
+    ShortAnswer,
+}
+
+// 模型生成的测验题目，结构与 QUIZ_DRAFT_SCHEMA 一致
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct QuizDraft {
+    #[validate(length(min = 1, max = 15, message = "题目数量必须在 1 到 15 之间"), nested)]
+    pub questions: Vec<QuizQuestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(deny_unknown_fields)]
+#[validate(schema(function = "validate_question"))]
+pub struct QuizQuestion {
+    #[serde(default = "Uuid::new_v4")]
+    #[schemars(skip)]
+    pub id: Uuid,
+    pub kind: QuestionKind,
+    #[validate(length(min = 1, max = 1000, message = "题干长度必须在 1 到 1000 个字符之间"))]
+    pub prompt: String,
+    // 选择题的选项，correct_option 为正确选项的下标（从 0 开始）
+    #[serde(default)]
+    #[schemars(length(min = 2, max = 6))]
+    pub options: Vec<String>,
+    pub correct_option: Option<usize>,
+    // 简答题的参考答案，以及评分时检查的关键词
+    #[serde(default)]
+    pub reference_answer: String,
+    #[serde(default)]
+    #[validate(length(max = 10, message = "关键词最多 10 个"))]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub explanation: String,
+}
+
+fn validate_question(question: &QuizQuestion) -> Result<(), ValidationError> {
+    let invalid = |message: &'static str| {
+        Err(ValidationError::new("question").with_message(std::borrow::Cow::Borrowed(message)))
+    };
+
+    match question.kind {
+        QuestionKind::MultipleChoice => {
+            if !(2..=6).contains(&question.options.len()) {
+                return invalid("选择题必须有 2 到 6 个选项");
+            }
+            if question.options.iter().any(|option| option.trim().is_empty()) {
+                return invalid("选项不能为空");
+            }
+            if question.correct_option.is_none_or(|index| index >= question.options.len()) {
+                return invalid("选择题的正确选项无效");
+            }
+        }
+        QuestionKind::ShortAnswer => {
+            // 空白关键词规范化后是空串，任何答案都会命中
+            if question.keywords.iter().any(|keyword| normalize_answer(keyword).is_empty()) {
+                return invalid("关键词不能为空");
+            }
+            if question.reference_answer.trim().is_empty() && question.keywords.is_empty() {
+                return invalid("简答题必须提供参考答案或关键词");
+            }
+        }
+    }
+    Ok(())
+}
+
+impl QuizDraft {
+    // 展示给学习者的文本字段，用于输出审核；关键词只用于评分，脱敏后反而无法命中，不参与审核
+    pub fn texts_mut(&mut self) -> Vec<&mut String> {
+        let mut texts = Vec::new();
+        for question in &mut self.questions {
+            texts.push(&mut question.prompt);
+            texts.extend(question.options.iter_mut());
+            texts.push(&mut question.reference_answer);
+            texts.push(&mut question.explanation);
+        }
+        texts
+    }
+}
+
+// 简答题命中关键词的比例达到该值即视为答对
+const SHORT_ANSWER_PASS_RATIO: f32 = 0.6;
+
+fn normalize_answer(text: &str) -> String {
+    text.split_whitespace().collect::<String>().to_lowercase()
+}
+
+impl QuizQuestion {
+    // 每题 1 分：选择题全对全错；简答题按命中的关键词比例给分，没有关键词时与参考答案比对
+    pub fn grade(&self, answer: Option<&QuizAnswer>) -> QuestionResult {
+        let score = match self.kind {
+            QuestionKind::MultipleChoice => {
+                let chosen = answer.and_then(|answer| answer.choice);
+                if chosen.is_some() && chosen == self.correct_option { 1.0 } else { 0.0 }
+            }
+            QuestionKind::ShortAnswer => {
+                let text = answer
+                    .and_then(|answer| answer.text.as_deref())
+                    .map(normalize_answer)
+                    .unwrap_or_default();
+                // 早先保存的测验可能含有空白关键词，评分时忽略
+                let keywords: Vec<String> = self
+                    .keywords
+                    .iter()
+                    .map(|keyword| normalize_answer(keyword))
+                    .filter(|keyword| !keyword.is_empty())
+                    .collect();
+                if text.is_empty() {
+                    0.0
+                } else if keywords.is_empty() {
+                    if text == normalize_answer(&self.reference_answer) { 1.0 } else { 0.0 }
+                } else {
+                    let matched = keywords.iter().filter(|keyword| text.contains(keyword.as_str())).count();
+                    matched as f32 / keywords.len() as f32
+                }
+            }
+        };
+
+        QuestionResult {
+            question_id: self.id,
+            score,
+            correct: match self.kind {
+                QuestionKind::MultipleChoice => score >= 1.0,
+                QuestionKind::ShortAnswer => score >= SHORT_ANSWER_PASS_RATIO,
+            },
+            correct_option: self.correct_option,
+            reference_answer: self.reference_answer.clone(),
+            explanation: self.explanation.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Quiz {
+    pub id: Uuid,
+    pub content_id: Uuid,
+    pub created_by: Uuid,
+    pub questions: Json<Vec<QuizQuestion>>,
+    // 生成测验所用的提示词版本，早于提示词版本管理的测验为空
+    pub prompt_version: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct QuizRequest {
+    pub content_id: Uuid,
+    #[validate(range(max = 10, message = "选择题数量不能超过 10"))]
+    pub multiple_choice: Option<u32>,
+    #[validate(range(max = 5, message = "简答题数量不能超过 5"))]
+    pub short_answer: Option<u32>,
+}
+
+// 返回给学习者的题目不包含答案
+#[derive(Debug, Serialize)]
+pub struct QuizQuestionResponse {
+    pub id: Uuid,
+    pub kind: QuestionKind,
+    pub prompt: String,
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuizResponse {
+    pub id: Uuid,
+    pub content_id: Uuid,
+    pub created_by: Uuid,
+    pub questions: Vec<QuizQuestionResponse>,
+    pub prompt: Option<PromptVersion>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Quiz> for QuizResponse {
+    fn from(quiz: Quiz) -> Self {
+        QuizResponse {
+            id: quiz.id,
+            content_id: quiz.content_id,
+            created_by: quiz.created_by,
+            questions: quiz
+                .questions
+                .0
+                .into_iter()
+                .map(|question| QuizQuestionResponse {
+                    id: question.id,
+                    kind: question.kind,
+                    prompt: question.prompt,
+                    options: question.options,
+                })
+                .collect(),
+            prompt: quiz.prompt_version.map(|version| PromptVersion {
+                name: QUIZ_PROMPT.name.to_string(),
+                version,
+            }),
+            created_at: quiz.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct QuizAnswer {
+    pub question_id: Uuid,
+    pub choice: Option<usize>,
+    #[validate(length(max = 2000, message = "答案长度不能超过 2000 个字符"))]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubmitQuizRequest {
+    #[validate(length(max = 15, message = "答案数量不能超过 15"), nested)]
+    pub answers: Vec<QuizAnswer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionResult {
+    pub question_id: Uuid,
+    pub score: f32,
+    pub correct: bool,
+    pub correct_option: Option<usize>,
+    pub reference_answer: String,
+    pub explanation: String,
+}
+
+impl Quiz {
+    // 返回逐题结果、得分和满分；未作答的题目计 0 分
+    pub fn grade(&self, answers: &[QuizAnswer]) -> (Vec<QuestionResult>, f32, f32) {
+        let results: Vec<QuestionResult> = self
+            .questions
+            .iter()
+            .map(|question| {
+                let answer = answers.iter().find(|answer| answer.question_id == question.id);
+                question.grade(answer)
+            })
+            .collect();
+        let score = results.iter().map(|result| result.score).sum();
+
+        (results, score, self.questions.len() as f32)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct QuizSubmission {
+    pub id: Uuid,
+    pub quiz_id: Uuid,
+    pub user_id: Uuid,
+    pub answers: Json<Vec<QuizAnswer>>,
+    pub results: Json<Vec<QuestionResult>>,
+    pub score: f32,
+    pub max_score: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct QuizSubmissionListQuery {
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在 1 到 100 之间"))]
+    pub limit: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choice_question(id: u128, correct_option: usize) -> QuizQuestion {
+        QuizQuestion {
+            id: Uuid::from_u128(id),
+            kind: QuestionKind::MultipleChoice,
+            prompt: "Rust 的值有几个所有者？".to_string(),
+            options: vec!["一个".to_string(), "两个".to_string(), "三个".to_string()],
+            correct_option: Some(correct_option),
+            reference_answer: String::new(),
+            keywords: Vec::new(),
+            explanation: "每个值只有一个所有者".to_string(),
+        }
+    }
+
+    fn short_question(id: u128, reference_answer: &str, keywords: &[&str]) -> QuizQuestion {
+        QuizQuestion {
+            id: Uuid::from_u128(id),
+            kind: QuestionKind::ShortAnswer,
+            prompt: "什么是借用？".to_string(),
+            options: Vec::new(),
+            correct_option: None,
+            reference_answer: reference_answer.to_string(),
+            keywords: keywords.iter().map(|keyword| keyword.to_string()).collect(),
+            explanation: String::new(),
+        }
+    }
+
+    fn choice(id: u128, choice: usize) -> QuizAnswer {
+        QuizAnswer { question_id: Uuid::from_u128(id), choice: Some(choice), text: None }
+    }
+
+    fn text(id: u128, text: &str) -> QuizAnswer {
+        QuizAnswer { question_id: Uuid::from_u128(id), choice: None, text: Some(text.to_string()) }
+    }
+
+    #[test]
+    fn multiple_choice_is_all_or_nothing() {
+        let question = choice_question(1, 0);
+
+        let result = question.grade(Some(&choice(1, 0)));
+        assert_eq!(result.score, 1.0);
+        assert!(result.correct);
+        assert_eq!(result.correct_option, Some(0));
+
+        let result = question.grade(Some(&choice(1, 2)));
+        assert_eq!(result.score, 0.0);
+        assert!(!result.correct);
+    }
+
+    #[test]
+    fn short_answer_scores_keyword_ratio() {
+        let question = short_question(1, "不转移所有权地使用值", &["所有权", "引用", "Borrow"]);
+
+        let result = question.grade(Some(&text(1, "通过引用使用值，不获取所有权")));
+        assert!((result.score - 2.0 / 3.0).abs() < 1e-6);
+        assert!(result.correct);
+
+        // 比较前去掉空白并转为小写
+        let result = question.grade(Some(&text(1, "b o r r o w")));
+        assert!((result.score - 1.0 / 3.0).abs() < 1e-6);
+        assert!(!result.correct);
+        assert_eq!(result.reference_answer, "不转移所有权地使用值");
+    }
+
+    #[test]
+    fn short_answer_without_keywords_matches_reference_answer() {
+        let question = short_question(1, "Move Semantics", &[]);
+
+        assert_eq!(question.grade(Some(&text(1, " move  semantics "))).score, 1.0);
+        assert_eq!(question.grade(Some(&text(1, "copy semantics"))).score, 0.0);
+    }
+
+    #[test]
+    fn missing_or_blank_answer_scores_zero() {
+        assert_eq!(choice_question(1, 0).grade(None).score, 0.0);
+
+        let question = short_question(1, "", &["所有权"]);
+        assert_eq!(question.grade(None).score, 0.0);
+        assert_eq!(question.grade(Some(&text(1, "   "))).score, 0.0);
+        assert_eq!(question.grade(Some(&choice(1, 0))).score, 0.0);
+    }
+
+    #[test]
+    fn blank_keywords_are_ignored_when_grading() {
+        let question = short_question(1, "", &["所有权", "  "]);
+
+        let result = question.grade(Some(&text(1, "和生命周期有关")));
+        assert_eq!(result.score, 0.0);
+        assert!(!result.correct);
+        assert_eq!(question.grade(Some(&text(1, "所有权"))).score, 1.0);
+    }
+
+    #[test]
+    fn drafts_with_blank_keywords_are_rejected() {
+        let draft = QuizDraft { questions: vec![short_question(1, "参考答案", &["所有权", " "])] };
+        assert!(draft.validate().is_err());
+
+        let draft = QuizDraft { questions: vec![short_question(1, "参考答案", &["所有权"])] };
+        assert!(draft.validate().is_ok());
+
+        let draft = QuizDraft { questions: vec![short_question(1, " ", &[])] };
+        assert!(draft.validate().is_err());
+    }
+
+    #[test]
+    fn quiz_totals_scores_and_skips_unanswered_questions() {
+        let quiz = Quiz {
+            id: Uuid::nil(),
+            content_id: Uuid::nil(),
+            created_by: Uuid::nil(),
+            questions: Json(vec![
+                choice_question(1, 1),
+                short_question(2, "", &["所有权", "引用"]),
+                choice_question(3, 0),
+            ]),
+            prompt_version: None,
+            created_at: Utc::now(),
+        };
+        // 未知题目的答案被忽略
+        let answers = vec![choice(1, 1), text(2, "引用"), choice(99, 0)];
+
+        let (results, score, max_score) = quiz.grade(&answers);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].score, 1.0);
+        assert_eq!(results[1].score, 0.5);
+        assert!(!results[1].correct);
+        assert_eq!(results[2].score, 0.0);
+        assert_eq!(score, 1.5);
+        assert_eq!(max_score, 3.0);
+    }
+}
@@ -0,0 +1,212 @@
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use sqlx::types::Json;
+use validator::Validate;
+
+pub static PLAN_DOCUMENT_SCHEMA: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::to_value(schemars::schema_for!(PlanDocument)).expect("plan schema is serializable")
+});
+
+// 学习计划正文。模型输出按由该结构生成的 JSON Schema 约束，再经反序列化和字段校验
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct PlanDocument {
+    #[validate(length(min = 1, max = 200, message = "计划标题长度必须在 1 到 200 个字符之间"))]
+    pub title: String,
+    #[serde(default)]
+    #[validate(length(max = 2000, message = "计划简介长度不能超过 2000 个字符"))]
+    pub summary: String,
+    #[validate(length(min = 1, max = 20, message = "里程碑数量必须在 1 到 20 之间"), nested)]
+    pub milestones: Vec<Milestone>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct Milestone {
+    #[validate(length(min = 1, max = 200, message = "里程碑标题长度必须在 1 到 200 个字符之间"))]
+    pub title: String,
+    #[serde(default)]
+    #[validate(length(max = 2000, message = "里程碑描述长度不能超过 2000 个字符"))]
+    pub description: String,
+    // 计划在第几周完成该里程碑
+    #[validate(range(min = 1, max = 52, message = "周数必须在 1 到 52 之间"))]
+    pub week: u32,
+    #[validate(length(min = 1, max = 30, message = "每个里程碑的任务数量必须在 1 到 30 之间"), nested)]
+    pub tasks: Vec<PlanTask>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct PlanTask {
+    // 任务 ID 和完成状态由服务端维护，不出现在给模型的 Schema 中
+    #[serde(default = "Uuid::new_v4")]
+    #[schemars(skip)]
+    pub id: Uuid,
+    #[validate(length(min = 1, max = 200, message = "任务标题长度必须在 1 到 200 个字符之间"))]
+    pub title: String,
+    #[serde(default)]
+    #[validate(length(max = 2000, message = "任务描述长度不能超过 2000 个字符"))]
+    pub description: String,
+    #[validate(range(min = 1, max = 52, message = "周数必须在 1 到 52 之间"))]
+    pub week: u32,
+    #[validate(range(min = 0.0, max = 200.0, message = "预计学时必须在 0 到 200 之间"))]
+    pub estimated_hours: f32,
+    // 关联的平台内容
+    #[serde(default)]
+    #[validate(length(max = 10, message = "每个任务最多关联 10 个内容"))]
+    pub content_ids: Vec<Uuid>,
+    #[serde(default)]
+    #[schemars(skip)]
+    pub completed: bool,
+    #[serde(default)]
+    #[schemars(skip)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl PlanDocument {
+    pub fn tasks(&self) -> impl Iterator<Item = &PlanTask> {
+        self.milestones.iter().flat_map(|milestone| milestone.tasks.iter())
+    }
+
+    pub fn tasks_mut(&mut self) -> impl Iterator<Item = &mut PlanTask> {
+        self.milestones.iter_mut().flat_map(|milestone| milestone.tasks.iter_mut())
+    }
+
+    // 计划中展示给用户的全部文本字段，用于输出审核
+    pub fn texts_mut(&mut self) -> Vec<&mut String> {
+        let mut texts = vec![&mut self.title, &mut self.summary];
+        for milestone in &mut self.milestones {
+            texts.push(&mut milestone.title);
+            texts.push(&mut milestone.description);
+            for task in &mut milestone.tasks {
+                texts.push(&mut task.title);
+                texts.push(&mut task.description);
+            }
+        }
+        texts
+    }
+
+    pub fn total_hours(&self) -> f32 {
+        self.tasks().map(|task| task.estimated_hours).sum()
+    }
+
+    pub fn content_ids(&self) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self.tasks().flat_map(|task| task.content_ids.iter().copied()).collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    // 重复的任务 ID（例如客户端复制任务时）重新分配，保证每个任务可以单独标记完成
+    pub fn ensure_unique_task_ids(&mut self) {
+        let mut seen = std::collections::HashSet::new();
+        for task in self.tasks_mut() {
+            if !seen.insert(task.id) {
+                task.id = Uuid::new_v4();
+                seen.insert(task.id);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StudyPlan {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub subject: String,
+    pub level: String,
+    pub goals: String,
+    pub plan: Json<PlanDocument>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StudyPlanRequest {
+    #[validate(
+        length(min = 1, max = 200, message = "学习主题长度必须在 1 到 200 个字符之间"),
+        custom(function = "crate::validation::not_blank")
+    )]
+    pub subject: String,
+    #[validate(length(min = 1, max = 50, message = "水平描述长度必须在 1 到 50 个字符之间"))]
+    pub level: String,
+    #[validate(length(max = 2000, message = "学习目标长度不能超过 2000 个字符"))]
+    pub goals: String,
+    // 学习周期（周）和每周可投入的学时，未提供时由模型自行安排
+    #[validate(range(min = 1, max = 52, message = "学习周期必须在 1 到 52 周之间"))]
+    pub weeks: Option<u32>,
+    #[validate(range(min = 1.0, max = 100.0, message = "每周学时必须在 1 到 100 之间"))]
+    pub hours_per_week: Option<f32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SaveStudyPlanRequest {
+    #[validate(
+        length(min = 1, max = 200, message = "学习主题长度必须在 1 到 200 个字符之间"),
+        custom(function = "crate::validation::not_blank")
+    )]
+    pub subject: String,
+    #[validate(length(min = 1, max = 50, message = "水平描述长度必须在 1 到 50 个字符之间"))]
+    pub level: String,
+    #[validate(length(max = 2000, message = "学习目标长度不能超过 2000 个字符"))]
+    pub goals: String,
+    #[validate(nested)]
+    pub plan: PlanDocument,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateStudyPlanRequest {
+    #[validate(nested)]
+    pub plan: PlanDocument,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePlanTaskRequest {
+    pub completed: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StudyPlanListQuery {
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在 1 到 100 之间"))]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudyPlanResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub subject: String,
+    pub level: String,
+    pub goals: String,
+    pub plan: PlanDocument,
+    pub total_hours: f32,
+    pub completed_tasks: usize,
+    pub total_tasks: usize,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<StudyPlan> for StudyPlanResponse {
+    fn from(study_plan: StudyPlan) -> Self {
+        let plan = study_plan.plan.0;
+        StudyPlanResponse {
+            id: study_plan.id,
+            user_id: study_plan.user_id,
+            subject: study_plan.subject,
+            level: study_plan.level,
+            goals: study_plan.goals,
+            total_hours: plan.total_hours(),
+            completed_tasks: plan.tasks().filter(|task| task.completed).count(),
+            total_tasks: plan.tasks().count(),
+            plan,
+            created_at: study_plan.created_at,
+            updated_at: study_plan.updated_at,
+        }
+    }
+}
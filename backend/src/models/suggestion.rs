@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::{Content, ContentResponse, PromptVersion};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SuggestionRequest {
+    #[serde(default)]
+    #[validate(custom(function = "crate::validation::tags"))]
+    pub interests: Vec<String>,
+    #[validate(custom(function = "crate::validation::content_type"))]
+    pub content_type: Option<String>,
+    #[validate(range(min = 1, max = 50, message = "推荐数量必须在 1 到 50 之间"))]
+    pub limit: Option<usize>,
+    // 是否让 AI 用自然语言解释推荐理由
+    #[serde(default)]
+    pub explain: bool,
+}
+
+// 候选内容及其社交信号：好友点赞数、是否由好友发布
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SuggestionCandidate {
+    #[sqlx(flatten)]
+    pub content: Content,
+    pub friend_likes: i64,
+    pub by_friend: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Suggestion {
+    pub content: ContentResponse,
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuggestionResponse {
+    pub interests: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+    pub explanation: Option<String>,
+    // 生成解释所用的提示词版本
+    pub prompt: Option<PromptVersion>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// 配额检查用的用量窗口：当天（UTC）累计，以及最近一分钟的请求数
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UsageWindow {
+    pub day_requests: i64,
+    pub day_tokens: i64,
+    pub minute_requests: i64,
+    pub minute_oldest: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AiUsageQuery {
+    // 统计区间，默认为当天（UTC）
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[validate(range(min = 1, max = 200, message = "用户数量必须在 1 到 200 之间"))]
+    pub limit: Option<i64>,
+}
+
+// user_id 为空的一行汇总所有匿名（按 IP 计量）的调用
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct UserUsage {
+    pub user_id: Option<Uuid>,
+    pub username: Option<String>,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct EndpointUsage {
+    pub endpoint: String,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+}
+
+// 按提示词版本汇总，用于对比同一提示词不同版本的效果
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PromptUsage {
+    pub prompt_name: String,
+    pub prompt_version: i32,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiUsageReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub users: Vec<UserUsage>,
+    pub endpoints: Vec<EndpointUsage>,
+    pub prompts: Vec<PromptUsage>,
+}
@@ -1,6 +1,20 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use crate::validation::USERNAME_RE;
+
+// 角色按权限从低到高排列，比较大小即可判断权限级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    Learner,
+    Teacher,
+    Moderator,
+    Admin,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct User {
@@ -11,27 +25,52 @@ pub struct User {
     pub display_name: Option<String>,
     pub bio: Option<String>,
     pub avatar_url: Option<String>,
+    pub role: UserRole,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateUserRequest {
+    #[validate(
+        length(min = 3, max = 32, message = "用户名长度必须在 3 到 32 个字符之间"),
+        regex(path = *USERNAME_RE, message = "用户名只能包含字母、数字、下划线和连字符")
+    )]
     pub username: String,
+    #[validate(email(message = "邮箱格式不正确"), length(max = 255, message = "邮箱长度不能超过 255 个字符"))]
     pub email: String,
+    #[validate(length(min = 8, max = 128, message = "密码长度必须在 8 到 128 个字符之间"))]
     pub password: String,
+    #[validate(length(max = 255, message = "显示名称长度不能超过 255 个字符"))]
     pub display_name: Option<String>,
+    #[validate(length(max = 2000, message = "个人简介长度不能超过 2000 个字符"))]
     pub bio: Option<String>,
+    #[validate(url(message = "头像地址必须是合法的 URL"), length(max = 500, message = "头像地址长度不能超过 500 个字符"))]
     pub avatar_url: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateUserRequest {
+    #[validate(length(max = 255, message = "显示名称长度不能超过 255 个字符"))]
     pub display_name: Option<String>,
+    #[validate(length(max = 2000, message = "个人简介长度不能超过 2000 个字符"))]
     pub bio: Option<String>,
+    #[validate(url(message = "头像地址必须是合法的 URL"), length(max = 500, message = "头像地址长度不能超过 500 个字符"))]
     pub avatar_url: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateUserRoleRequest {
+    pub role: UserRole,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UserListQuery {
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在 1 到 100 之间"))]
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserResponse {
     pub id: Uuid,
@@ -40,6 +79,7 @@ pub struct UserResponse {
     pub display_name: Option<String>,
     pub bio: Option<String>,
     pub avatar_url: Option<String>,
+    pub role: UserRole,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -53,8 +93,35 @@ impl From<User> for UserResponse {
             display_name: user.display_name,
             bio: user.bio,
             avatar_url: user.avatar_url,
+            role: user.role,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
     }
 }
+
+// 对外公开的用户资料，不含邮箱；只有本人和管理员能看到完整的 UserResponse
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PublicUserResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub role: UserRole,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<User> for PublicUserResponse {
+    fn from(user: User) -> Self {
+        PublicUserResponse {
+            id: user.id,
+            username: user.username,
+            display_name: user.display_name,
+            bio: user.bio,
+            avatar_url: user.avatar_url,
+            role: user.role,
+            created_at: user.created_at,
+        }
+    }
+}
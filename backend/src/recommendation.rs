@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{Suggestion, SuggestionCandidate};
+
+// 各项信号的权重：兴趣标签 > 点赞历史 > 好友动态 > 整体热度
+const INTEREST_WEIGHT: f64 = 3.0;
+const HISTORY_WEIGHT: f64 = 2.0;
+const FRIEND_LIKE_WEIGHT: f64 = 1.0;
+const FRIEND_AUTHOR_BONUS: f64 = 0.5;
+const POPULARITY_WEIGHT: f64 = 0.2;
+
+// 推荐所需的用户画像：感兴趣的标签，以及点赞过的内容中各标签出现的次数
+pub struct UserProfile {
+    pub interests: HashSet<String>,
+    pub liked_tags: HashMap<String, i64>,
+}
+
+impl UserProfile {
+    pub fn new(interests: &[String], liked_tags: Vec<(String, i64)>) -> Self {
+        Self {
+            interests: interests.iter().map(|tag| normalize(tag)).collect(),
+            liked_tags: liked_tags
+                .into_iter()
+                .map(|(tag, count)| (normalize(&tag), count))
+                .collect(),
+        }
+    }
+}
+
+fn normalize(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+// 标签按原样存储，数据库预筛选时同时用原始写法和小写形式匹配
+pub fn tag_variants(tags: &[String]) -> Vec<String> {
+    let mut variants: Vec<String> = tags
+        .iter()
+        .flat_map(|tag| [tag.trim().to_string(), normalize(tag)])
+        .collect();
+    variants.sort();
+    variants.dedup();
+    variants
+}
+
+fn score(profile: &UserProfile, candidate: &SuggestionCandidate) -> (f64, Vec<String>) {
+    let tags: HashSet<String> = candidate.content.tags.iter().map(|tag| normalize(tag)).collect();
+    let mut total = 0.0;
+    let mut reasons = Vec::new();
+
+    if !profile.interests.is_empty() {
+        let mut matched: Vec<&String> = tags.intersection(&profile.interests).collect();
+        if !matched.is_empty() {
+            matched.sort();
+            total += INTEREST_WEIGHT * matched.len() as f64 / profile.interests.len() as f64;
+            let matched: Vec<&str> = matched.into_iter().map(String::as_str).collect();
+            reasons.push(format!("匹配兴趣标签：{}", matched.join("、")));
+        }
+    }
+
+    let liked_total: i64 = profile.liked_tags.values().sum();
+    if liked_total > 0 {
+        let affinity: i64 = tags.iter().filter_map(|tag| profile.liked_tags.get(tag)).sum();
+        if affinity > 0 {
+            total += HISTORY_WEIGHT * affinity as f64 / liked_total as f64;
+            reasons.push("与你点赞过的内容主题相近".to_string());
+        }
+    }
+
+    if candidate.friend_likes > 0 {
+        total += FRIEND_LIKE_WEIGHT * (1.0 + candidate.friend_likes as f64).ln();
+        reasons.push(format!("{} 位好友点赞", candidate.friend_likes));
+    }
+    if candidate.by_friend {
+        total += FRIEND_AUTHOR_BONUS;
+        reasons.push("好友发布".to_string());
+    }
+
+    total += POPULARITY_WEIGHT * (1.0 + candidate.content.likes_count.max(0) as f64).ln();
+
+    (total, reasons)
+}
+
+// 按得分从高到低排序，得分相同时较新的内容优先
+pub fn rank(profile: &UserProfile, candidates: Vec<SuggestionCandidate>, limit: usize) -> Vec<Suggestion> {
+    let mut scored: Vec<(f64, Vec<String>, SuggestionCandidate)> = candidates
+        .into_iter()
+        .map(|candidate| {
+            let (score, reasons) = score(profile, &candidate);
+            (score, reasons, candidate)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| b.2.content.created_at.cmp(&a.2.content.created_at))
+    });
+
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(score, reasons, candidate)| Suggestion {
+            content: candidate.content.into(),
+            score: (score * 1000.0).round() / 1000.0,
+            reasons,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::Content;
+
+    fn candidate(id: u128, tags: &[&str], likes_count: i32, friend_likes: i64, by_friend: bool) -> SuggestionCandidate {
+        let created_at: DateTime<Utc> = DateTime::from_timestamp(1_700_000_000 + id as i64, 0).unwrap();
+        SuggestionCandidate {
+            content: Content {
+                id: Uuid::from_u128(id),
+                title: format!("content {id}"),
+                body: String::new(),
+                content_type: "article".to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                author_id: Uuid::nil(),
+                likes_count,
+                created_at,
+                updated_at: created_at,
+            },
+            friend_likes,
+            by_friend,
+        }
+    }
+
+    fn profile(interests: &[&str], liked_tags: &[(&str, i64)]) -> UserProfile {
+        let interests: Vec<String> = interests.iter().map(|tag| tag.to_string()).collect();
+        let liked_tags = liked_tags.iter().map(|(tag, count)| (tag.to_string(), *count)).collect();
+        UserProfile::new(&interests, liked_tags)
+    }
+
+    fn ids(suggestions: &[Suggestion]) -> Vec<Uuid> {
+        suggestions.iter().map(|suggestion| suggestion.content.id).collect()
+    }
+
+    #[test]
+    fn interest_tags_outrank_popularity() {
+        let candidates = vec![
+            candidate(1, &["python"], 500, 0, false),
+            candidate(2, &["Rust"], 0, 0, false),
+        ];
+        let ranked = rank(&profile(&[" rust "], &[]), candidates, 10);
+
+        assert_eq!(ids(&ranked), vec![Uuid::from_u128(2), Uuid::from_u128(1)]);
+        assert_eq!(ranked[0].reasons, vec!["匹配兴趣标签：rust".to_string()]);
+        assert_eq!(ranked[0].score, 3.0);
+        assert!(ranked[1].reasons.is_empty());
+    }
+
+    #[test]
+    fn history_and_friends_add_reasons() {
+        let candidates = vec![
+            candidate(1, &["go"], 0, 0, false),
+            candidate(2, &["go"], 0, 3, true),
+            candidate(3, &["sql"], 0, 0, false),
+        ];
+        let ranked = rank(&profile(&[], &[("SQL", 4)]), candidates, 10);
+
+        assert_eq!(ids(&ranked), vec![Uuid::from_u128(3), Uuid::from_u128(2), Uuid::from_u128(1)]);
+        assert_eq!(ranked[0].reasons, vec!["与你点赞过的内容主题相近".to_string()]);
+        assert_eq!(ranked[1].reasons, vec!["3 位好友点赞".to_string(), "好友发布".to_string()]);
+    }
+
+    #[test]
+    fn ties_prefer_newer_content_and_limit_applies() {
+        let candidates = (1..=5).map(|id| candidate(id, &[], 0, 0, false)).collect();
+        let ranked = rank(&profile(&[], &[]), candidates, 3);
+
+        assert_eq!(ids(&ranked), vec![Uuid::from_u128(5), Uuid::from_u128(4), Uuid::from_u128(3)]);
+        assert!(ranked.iter().all(|suggestion| suggestion.score == 0.0));
+    }
+
+    #[test]
+    fn tag_variants_cover_original_and_lowercase() {
+        let tags = vec![" Rust ".to_string(), "rust".to_string(), "SQL".to_string()];
+        assert_eq!(tag_variants(&tags), vec!["Rust", "SQL", "rust", "sql"]);
+    }
+}
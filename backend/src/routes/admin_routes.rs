@@ -0,0 +1,21 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::handlers::admin_handler::{
+    get_ai_usage, get_prompt_templates, create_prompt_template, update_prompt_template,
+    get_moderation_rules, create_moderation_rule, update_moderation_rule, delete_moderation_rule,
+    get_moderation_incidents
+};
+use crate::state::AppState;
+
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/ai-usage", get(get_ai_usage))
+        .route("/prompts", get(get_prompt_templates).post(create_prompt_template))
+        .route("/prompts/{id}", put(update_prompt_template))
+        .route("/moderation/rules", get(get_moderation_rules).post(create_moderation_rule))
+        .route("/moderation/rules/{id}", put(update_moderation_rule).delete(delete_moderation_rule))
+        .route("/moderation/incidents", get(get_moderation_incidents))
+}
@@ -1,16 +1,30 @@
 use axum::{
-    routing::post,
+    middleware,
+    routing::{get, post},
     Router,
 };
 
+use crate::ai::usage::enforce_quota;
 use crate::handlers::ai_handler::{
-    chat_with_ai, get_study_plan, get_learning_suggestions
+    chat_with_ai, chat_with_ai_stream, generate_quiz, get_study_plan, get_learning_suggestions
 };
-use crate::database::Database;
+use crate::handlers::conversation_handler::{
+    get_conversations, create_conversation, get_conversation, delete_conversation
+};
+use crate::state::AppState;
 
-pub fn ai_routes() -> Router<Database> {
-    Router::new()
+pub fn ai_routes(state: AppState) -> Router<AppState> {
+    // 调用模型的接口计入 AI 用量并受配额限制，会话管理接口不计量
+    let metered = Router::new()
         .route("/chat", post(chat_with_ai))
+        .route("/chat/stream", post(chat_with_ai_stream))
+        .route("/quiz", post(generate_quiz))
         .route("/study-plan", post(get_study_plan))
         .route("/suggestions", post(get_learning_suggestions))
+        .route_layer(middleware::from_fn_with_state(state, enforce_quota));
+
+    Router::new()
+        .route("/conversations", get(get_conversations).post(create_conversation))
+        .route("/conversations/{id}", get(get_conversation).delete(delete_conversation))
+        .merge(metered)
 }
@@ -0,0 +1,17 @@
+use axum::{
+    routing::post,
+    Router,
+};
+
+use crate::handlers::auth_handler::{
+    register, login, refresh, logout
+};
+use crate::state::AppState;
+
+pub fn auth_routes() -> Router<AppState> {
+    Router::new()
+        .route("/register", post(register))
+        .route("/login", post(login))
+        .route("/refresh", post(refresh))
+        .route("/logout", post(logout))
+}
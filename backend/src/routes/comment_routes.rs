@@ -0,0 +1,15 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::handlers::comment_handler::{
+    update_comment, delete_comment, get_replies, create_reply
+};
+use crate::state::AppState;
+
+pub fn comment_routes() -> Router<AppState> {
+    Router::new()
+        .route("/{id}", put(update_comment).delete(delete_comment))
+        .route("/{id}/replies", get(get_replies).post(create_reply))
+}
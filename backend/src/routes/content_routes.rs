@@ -1,16 +1,20 @@
 use axum::{
-    routing::{get, post, put, delete},
+    routing::{get, post},
     Router,
 };
 
 use crate::handlers::content_handler::{
     get_contents, create_content, get_content_by_id, update_content, delete_content, like_content, unlike_content
 };
-use crate::database::Database;
+use crate::handlers::comment_handler::{
+    get_comments, create_comment
+};
+use crate::state::AppState;
 
-pub fn content_routes() -> Router<Database> {
+pub fn content_routes() -> Router<AppState> {
     Router::new()
         .route("/", get(get_contents).post(create_content))
         .route("/{id}", get(get_content_by_id).put(update_content).delete(delete_content))
         .route("/{id}/like", post(like_content).delete(unlike_content))
+        .route("/{id}/comments", get(get_comments).post(create_comment))
 }
@@ -0,0 +1,19 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+
+use crate::handlers::friendship_handler::{
+    get_friends, send_friend_request, accept_friend_request, decline_friend_request, block_user, unblock_user, remove_friend
+};
+use crate::state::AppState;
+
+pub fn friendship_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_friends))
+        .route("/requests", post(send_friend_request))
+        .route("/requests/{id}/accept", post(accept_friend_request))
+        .route("/requests/{id}/decline", post(decline_friend_request))
+        .route("/{user_id}", delete(remove_friend))
+        .route("/{user_id}/block", post(block_user).delete(unblock_user))
+}
@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+
+use crate::handlers::health_handler::{healthz, readyz};
+use crate::state::AppState;
+
+pub fn health_routes() -> Router<AppState> {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+}
@@ -1,7 +1,21 @@
+pub mod auth_routes;
 pub mod user_routes;
 pub mod content_routes;
+pub mod comment_routes;
+pub mod friendship_routes;
+pub mod study_plan_routes;
+pub mod quiz_routes;
 pub mod ai_routes;
+pub mod admin_routes;
+pub mod health_routes;
 
+pub use auth_routes::auth_routes;
 pub use user_routes::user_routes;
 pub use content_routes::content_routes;
+pub use comment_routes::comment_routes;
+pub use friendship_routes::friendship_routes;
+pub use study_plan_routes::study_plan_routes;
+pub use quiz_routes::quiz_routes;
 pub use ai_routes::ai_routes;
+pub use admin_routes::admin_routes;
+pub use health_routes::health_routes;
@@ -0,0 +1,15 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::quiz_handler::{
+    get_quiz_by_id, submit_quiz, get_quiz_submissions
+};
+use crate::state::AppState;
+
+pub fn quiz_routes() -> Router<AppState> {
+    Router::new()
+        .route("/{id}", get(get_quiz_by_id))
+        .route("/{id}/submissions", post(submit_quiz).get(get_quiz_submissions))
+}
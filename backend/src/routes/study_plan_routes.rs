@@ -0,0 +1,16 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::handlers::study_plan_handler::{
+    get_study_plans, create_study_plan, get_study_plan_by_id, update_study_plan, delete_study_plan, update_study_plan_task
+};
+use crate::state::AppState;
+
+pub fn study_plan_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_study_plans).post(create_study_plan))
+        .route("/{id}", get(get_study_plan_by_id).put(update_study_plan).delete(delete_study_plan))
+        .route("/{id}/tasks/{task_id}", put(update_study_plan_task))
+}
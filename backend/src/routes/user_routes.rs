@@ -1,15 +1,16 @@
 use axum::{
-    routing::{get, post, put, delete},
+    routing::{get, put},
     Router,
 };
 
 use crate::handlers::user_handler::{
-    get_users, create_user, get_user_by_id, update_user, delete_user
+    get_users, create_user, get_user_by_id, update_user, update_user_role, delete_user
 };
-use crate::database::Database;
+use crate::state::AppState;
 
-pub fn user_routes() -> Router<Database> {
+pub fn user_routes() -> Router<AppState> {
     Router::new()
         .route("/", get(get_users).post(create_user))
         .route("/{id}", get(get_user_by_id).put(update_user).delete(delete_user))
+        .route("/{id}/role", put(update_user_role))
 }
@@ -0,0 +1,56 @@
+use std::{future::IntoFuture, io, net::SocketAddr, time::Duration};
+
+use axum::Router;
+use tokio::{net::TcpListener, sync::watch};
+
+// 等待 SIGINT（Ctrl+C）或 SIGTERM（容器停止、滚动发布时发送）
+async fn signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("收到 SIGINT，开始停止服务"),
+        _ = terminate => tracing::info!("收到 SIGTERM，开始停止服务"),
+    }
+}
+
+// 收到停止信号后不再接受新连接，等待进行中的请求（包括流式回答）完成；超过 deadline 仍未完成的连接被强制断开
+pub async fn serve(listener: TcpListener, app: Router, deadline: Duration) -> io::Result<()> {
+    let (draining_tx, mut draining_rx) = watch::channel(false);
+    // 匿名 AI 调用按客户端地址计量，需要连接信息
+    let server = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(async move {
+            signal().await;
+            let _ = draining_tx.send(true);
+        })
+        .into_future();
+    let mut server = tokio::spawn(server);
+
+    tokio::select! {
+        result = &mut server => return result?,
+        _ = draining_rx.changed() => {}
+    }
+
+    match tokio::time::timeout(deadline, &mut server).await {
+        Ok(result) => {
+            tracing::info!("进行中的请求已全部完成");
+            result?
+        }
+        Err(_) => {
+            tracing::warn!("in-flight requests did not finish within {:?}, closing remaining connections", deadline);
+            server.abort();
+            Ok(())
+        }
+    }
+}
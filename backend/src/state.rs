@@ -0,0 +1,55 @@
+use axum::extract::FromRef;
+
+use crate::ai::{ContentIndex, Moderator, PromptRegistry, SharedAiProvider, UsagePolicy};
+use crate::auth::JwtKeys;
+use crate::database::Database;
+use crate::migrate::MigrateMode;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub db: Database,
+    pub jwt: JwtKeys,
+    pub ai: SharedAiProvider,
+    pub index: ContentIndex,
+    pub usage: UsagePolicy,
+    pub prompts: PromptRegistry,
+    pub moderator: Moderator,
+    // 就绪检查按启动时的迁移策略决定是否检查数据库结构
+    pub migrate: MigrateMode,
+}
+
+impl FromRef<AppState> for Database {
+    fn from_ref(state: &AppState) -> Self {
+        state.db.clone()
+    }
+}
+
+impl FromRef<AppState> for JwtKeys {
+    fn from_ref(state: &AppState) -> Self {
+        state.jwt.clone()
+    }
+}
+
+impl FromRef<AppState> for SharedAiProvider {
+    fn from_ref(state: &AppState) -> Self {
+        state.ai.clone()
+    }
+}
+
+impl FromRef<AppState> for ContentIndex {
+    fn from_ref(state: &AppState) -> Self {
+        state.index.clone()
+    }
+}
+
+impl FromRef<AppState> for PromptRegistry {
+    fn from_ref(state: &AppState) -> Self {
+        state.prompts.clone()
+    }
+}
+
+impl FromRef<AppState> for Moderator {
+    fn from_ref(state: &AppState) -> Self {
+        state.moderator.clone()
+    }
+}
@@ -0,0 +1,139 @@
+use std::{borrow::Cow, collections::BTreeMap, sync::LazyLock};
+
+use axum::{
+    extract::{rejection::JsonRejection, FromRequest, FromRequestParts, Query, Request},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::error::AppError;
+
+pub const CONTENT_TYPES: &[&str] = &["article", "video", "audio", "course", "quiz"];
+pub const MAX_TAGS: usize = 10;
+pub const MAX_TAG_LENGTH: usize = 30;
+
+// 用户名允许字母（含中文）、数字、下划线和连字符
+pub static USERNAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[\p{L}\p{N}_-]+$").expect("invalid username regex"));
+
+// 先按 Json 解析，再执行 Validate 校验，失败时返回 422 和逐字段的错误信息
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(json_rejection)?;
+
+        value.validate()?;
+
+        Ok(ValidatedJson(value))
+    }
+}
+
+// 查询参数版本的 ValidatedJson
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| AppError::BadRequest(rejection.body_text()))?;
+
+        value.validate()?;
+
+        Ok(ValidatedQuery(value))
+    }
+}
+
+fn json_rejection(rejection: JsonRejection) -> AppError {
+    match rejection.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => AppError::PayloadTooLarge,
+        _ => AppError::BadRequest(rejection.body_text()),
+    }
+}
+
+// 将 validator 的错误整理成 { 字段: [错误信息...] }，嵌套字段展开为 plan.milestones[0].title 形式
+pub fn field_errors(errors: &ValidationErrors) -> BTreeMap<String, Vec<String>> {
+    let mut fields = BTreeMap::new();
+    collect_field_errors(errors, "", &mut fields);
+    fields
+}
+
+fn collect_field_errors(errors: &ValidationErrors, prefix: &str, fields: &mut BTreeMap<String, Vec<String>>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                let messages = errors
+                    .iter()
+                    .map(|error| {
+                        error
+                            .message
+                            .as_ref()
+                            .map(|message| message.to_string())
+                            .unwrap_or_else(|| error.code.to_string())
+                    })
+                    .collect();
+                fields.insert(path, messages);
+            }
+            ValidationErrorsKind::Struct(errors) => collect_field_errors(errors, &path, fields),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect_field_errors(errors, &format!("{}[{}]", path, index), fields);
+                }
+            }
+        }
+    }
+}
+
+fn error(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
+
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(error("blank", "不能为空"));
+    }
+    Ok(())
+}
+
+pub fn content_type(value: &str) -> Result<(), ValidationError> {
+    if !CONTENT_TYPES.contains(&value) {
+        return Err(error("content_type", "内容类型必须是 article、video、audio、course 或 quiz"));
+    }
+    Ok(())
+}
+
+pub fn tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.len() > MAX_TAGS {
+        return Err(error("tags_count", "标签最多 10 个"));
+    }
+    if tags
+        .iter()
+        .any(|tag| tag.trim().is_empty() || tag.chars().count() > MAX_TAG_LENGTH)
+    {
+        return Err(error("tag_length", "每个标签长度必须在 1 到 30 个字符之间"));
+    }
+    Ok(())
+}
@@ -17,16 +17,25 @@ echo "👥 测试获取所有用户..."
 curl -s "$BASE_URL/api/users" | jq .
 echo -e "\n"
 
-# 测试创建用户
-echo "➕ 测试创建用户..."
-USER_RESPONSE=$(curl -s -X POST "$BASE_URL/api/users" \
+# 测试注册用户
+echo "➕ 测试注册用户..."
+USER_RESPONSE=$(curl -s -X POST "$BASE_URL/api/auth/register" \
   -H "Content-Type: application/json" \
-  -d '{"username": "alice", "email": "alice@example.com", "password": "password123"}')
+  -d '{"username": "alice", "email": "alice@example.com", "password": "password123", "confirm_password": "password123"}')
 echo "$USER_RESPONSE" | jq .
 USER_ID=$(echo "$USER_RESPONSE" | jq -r '.user.id // .id // empty')
 echo "用户 ID: $USER_ID"
 echo -e "\n"
 
+# 测试登录
+echo "🔑 测试登录..."
+LOGIN_RESPONSE=$(curl -s -X POST "$BASE_URL/api/auth/login" \
+  -H "Content-Type: application/json" \
+  -d '{"username": "alice", "password": "password123"}')
+echo "$LOGIN_RESPONSE" | jq .
+ACCESS_TOKEN=$(echo "$LOGIN_RESPONSE" | jq -r '.tokens.access_token // empty')
+echo -e "\n"
+
 # 测试获取特定用户
 if [ ! -z "$USER_ID" ]; then
     echo "🔍 测试获取特定用户..."
@@ -41,10 +50,11 @@ echo -e "\n"
 
 # 测试创建内容
 echo "➕ 测试创建内容..."
-if [ ! -z "$USER_ID" ]; then
+if [ ! -z "$ACCESS_TOKEN" ]; then
     CONTENT_RESPONSE=$(curl -s -X POST "$BASE_URL/api/contents" \
       -H "Content-Type: application/json" \
-      -d "{\"title\": \"Alice的第一篇文章\", \"body\": \"这是一篇关于学习的心得体会\", \"author\": \"$USER_ID\", \"tags\": [\"学习\", \"心得\"]}")
+      -H "Authorization: Bearer $ACCESS_TOKEN" \
+      -d '{"title": "Alice的第一篇文章", "body": "这是一篇关于学习的心得体会", "content_type": "article", "tags": ["学习", "心得"]}')
     echo "$CONTENT_RESPONSE" | jq .
     CONTENT_ID=$(echo "$CONTENT_RESPONSE" | jq -r '.content.id // .id // empty')
     echo "内容 ID: $CONTENT_ID"
@@ -52,11 +62,10 @@ if [ ! -z "$USER_ID" ]; then
 fi
 
 # 测试点赞功能
-if [ ! -z "$CONTENT_ID" ] && [ ! -z "$USER_ID" ]; then
+if [ ! -z "$CONTENT_ID" ] && [ ! -z "$ACCESS_TOKEN" ]; then
     echo "👍 测试点赞功能..."
     curl -s -X POST "$BASE_URL/api/contents/$CONTENT_ID/like" \
-      -H "Content-Type: application/json" \
-      -d "{\"user_id\": \"$USER_ID\"}" | jq .
+      -H "Authorization: Bearer $ACCESS_TOKEN" | jq .
     echo -e "\n"
     
     echo "🔍 查看点赞后的内容状态..."